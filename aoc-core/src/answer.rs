@@ -0,0 +1,75 @@
+use std::fmt;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// What a day found out about its puzzle input.
+///
+/// A part is `None` if the day does not solve it (yet). `metadata` holds
+/// additional named facts about the solution, e.g. which bingo board won.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Answer {
+    pub part1: Option<usize>,
+    pub part2: Option<usize>,
+    pub metadata: Vec<(&'static str, String)>,
+}
+
+impl Answer {
+    pub fn new(part1: Option<usize>, part2: Option<usize>) -> Self {
+        Answer {
+            part1,
+            part2,
+            metadata: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, key: &'static str, value: impl fmt::Display) -> Self {
+        self.metadata.push((key, value.to_string()));
+        self
+    }
+
+    pub fn part(&self, part: Part) -> Option<usize> {
+        match part {
+            Part::One => self.part1,
+            Part::Two => self.part2,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.metadata
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for part in Part::ALL {
+            match self.part(part) {
+                Some(answer) => writeln!(f, "part {}: {}", part, answer)?,
+                None => writeln!(f, "part {}: not solved yet", part)?,
+            }
+        }
+        for (key, value) in &self.metadata {
+            writeln!(f, "{}: {}", key, value)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use std::ops::RangeInclusive;
+
+use crate::{Generate, Result, Rng, Solution};
+
+/// How many generated inputs [`check_generated`] answers, one per seed from 0.
+const SEEDS: u64 = 20;
+
+/// Answers inputs that `S` generates and compares both parts with `reference`, which solves
+/// the parsed input some plainer way. Every seed draws the size of its input from `sizes`.
+///
+/// Meant for tests, panics with the seed and size of the first input answered differently.
+pub fn check_generated<S: Solution + Generate>(
+    sizes: RangeInclusive<usize>,
+    reference: impl Fn(&S) -> [Option<usize>; 2],
+) -> Result<()> {
+    for seed in 0..SEEDS {
+        let mut rng = Rng::new(seed);
+        let size = rng.range(sizes.clone());
+        let solution = S::parse(&S::generate(&mut rng, size))?;
+
+        let answer = solution.answer()?;
+        assert_eq!(
+            [answer.part1, answer.part2],
+            reference(&solution),
+            "seed {}, size {}",
+            seed,
+            size
+        );
+    }
+
+    Ok(())
+}
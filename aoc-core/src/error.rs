@@ -0,0 +1,122 @@
+use std::{fmt, io, str::FromStr};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Reading the puzzle input failed.
+    Io(io::Error),
+    /// The puzzle input is malformed, `line` and `column` start at 1.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The puzzle input is well-formed, but cannot be solved.
+    InvalidPuzzle(String),
+}
+
+impl Error {
+    pub fn parse(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Error::Parse {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Error::InvalidPuzzle(message.into())
+    }
+
+    /// Moves a parse error reported for a snippet of the input to where that snippet
+    /// starts, snippets parsed on their own report their position from line 1, column 1.
+    pub fn relative_to(self, line: usize, column: usize) -> Self {
+        match self {
+            Error::Parse {
+                line: inner_line,
+                column: inner_column,
+                message,
+            } => Error::Parse {
+                line: line + inner_line - 1,
+                column: match inner_line {
+                    1 => column + inner_column - 1,
+                    _ => inner_column,
+                },
+                message,
+            },
+            other => other,
+        }
+    }
+}
+
+/// Column (starting at 1) where `token` starts, `token` has to be a slice of `line`.
+pub fn column_of(line: &str, token: &str) -> usize {
+    let offset = token.as_ptr() as usize - line.as_ptr() as usize;
+    debug_assert!(
+        offset <= line.len(),
+        "{:?} is not part of {:?}",
+        token,
+        line
+    );
+    line[..offset].chars().count() + 1
+}
+
+/// Parses `token`, a slice of `line`, reporting failures at its position on that line.
+pub fn parse_token<T>(line: &str, token: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    token.parse::<T>().map_err(|e| {
+        Error::parse(
+            1,
+            column_of(line, token),
+            format!("could not parse {:?}: {}", token, e),
+        )
+    })
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "line {}, column {}: {}", line, column, message),
+            Error::InvalidPuzzle(message) => write!(f, "invalid puzzle: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+#[test]
+fn test_parse_token_reports_position() {
+    let line = "3,4,x,1";
+    let token = line.split(',').nth(2).unwrap();
+
+    let error = parse_token::<u8>(line, token)
+        .unwrap_err()
+        .relative_to(7, 1);
+
+    assert_eq!(
+        error.to_string(),
+        "line 7, column 5: could not parse \"x\": invalid digit found in string"
+    );
+}
@@ -0,0 +1,52 @@
+use std::{
+    fs,
+    io::{self, Read},
+};
+
+use flate2::read::GzDecoder;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Reads puzzle input from `path`, `-` reads stdin, gzip-compressed input is decompressed.
+///
+/// The input is [normalized](normalize) like every day's parser does it.
+pub fn load(path: &str) -> io::Result<String> {
+    let mut bytes = Vec::new();
+    match path {
+        "-" => io::stdin().lock().read_to_end(&mut bytes)?,
+        _ => fs::File::open(path)?.read_to_end(&mut bytes)?,
+    };
+
+    let text = match bytes.starts_with(&GZIP_MAGIC) {
+        true => {
+            let mut text = String::new();
+            GzDecoder::new(bytes.as_slice()).read_to_string(&mut text)?;
+            text
+        }
+        false => {
+            String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+        }
+    };
+
+    Ok(normalize(&text))
+}
+
+/// Ends every line with `\n` instead of `\r\n` and drops trailing whitespace on every
+/// line and trailing blank lines, leading whitespace is kept.
+pub fn normalize(input: &str) -> String {
+    let mut normalized = String::with_capacity(input.len());
+    for line in input.trim_end().lines() {
+        normalized.push_str(line.trim_end());
+        normalized.push('\n');
+    }
+    normalized
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(
+        normalize("3,4 \r\n\r\n 22  1\t\r\n\r\n\n"),
+        "3,4\n\n 22  1\n"
+    );
+    assert_eq!(normalize(""), "");
+}
@@ -0,0 +1,37 @@
+mod answer;
+mod check;
+mod error;
+mod input;
+mod rng;
+
+pub use answer::{Answer, Part};
+pub use check::check_generated;
+pub use error::{column_of, parse_token, Error, Result};
+pub use input::{load, normalize};
+pub use rng::Rng;
+
+/// A single day of the calendar.
+///
+/// The puzzle input is parsed once into the day's own model, both parts are
+/// then answered from that model. Days that do not solve a part (yet) return
+/// `None` for it.
+pub trait Solution: Sized {
+    fn parse(input: &str) -> Result<Self>;
+
+    fn part1(&self) -> Result<Option<usize>>;
+
+    fn part2(&self) -> Result<Option<usize>>;
+
+    /// Answers both parts, days override this to add metadata to their answer.
+    fn answer(&self) -> Result<Answer> {
+        Ok(Answer::new(self.part1()?, self.part2()?))
+    }
+}
+
+/// Days that can make up valid puzzle input of their own, e.g. to stress their solution.
+pub trait Generate {
+    /// What `size` means is up to the day, e.g. the number of bingo boards.
+    const DEFAULT_SIZE: usize;
+
+    fn generate(rng: &mut Rng, size: usize) -> String;
+}
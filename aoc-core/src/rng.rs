@@ -0,0 +1,60 @@
+use std::ops::RangeInclusive;
+
+/// A small seeded random number generator (SplitMix64).
+///
+/// Generated puzzles have to stay the same for a seed, so this does not depend on
+/// an external crate that might change its streams between versions.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "cannot pick from {}..={}", start, end);
+        match (end - start).checked_add(1) {
+            Some(width) => start + (self.next_u64() % width as u64) as usize,
+            None => self.next_u64() as usize,
+        }
+    }
+
+    /// `true` with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..=i));
+        }
+    }
+}
+
+#[test]
+fn test_rng_is_reproducible() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+
+    let numbers: Vec<usize> = (0..100).map(|_| a.range(3..=7)).collect();
+
+    assert_eq!(
+        numbers,
+        (0..100).map(|_| b.range(3..=7)).collect::<Vec<_>>()
+    );
+    assert!(numbers.iter().all(|n| (3..=7).contains(n)));
+    assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+}
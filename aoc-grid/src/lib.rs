@@ -0,0 +1,260 @@
+mod render;
+
+pub use render::{palette, save, scale, write, Format, Render, Rgb};
+
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+    slice,
+};
+
+use aoc_core::{Error, Result};
+
+/// A position on a grid as `(row, column)`, both start at 0.
+pub type Position = (usize, usize);
+
+const NEIGHBOURS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    columns: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(rows: usize, columns: usize, value: T) -> Self {
+        Grid {
+            cells: vec![value; rows * columns],
+            rows,
+            columns,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(rows: usize, columns: usize, mut f: impl FnMut(Position) -> T) -> Self {
+        let cells = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (row, column)))
+            .map(&mut f)
+            .collect();
+
+        Grid {
+            cells,
+            rows,
+            columns,
+        }
+    }
+
+    /// Reads a map with one row per line, `cell` turns a character into a cell or
+    /// explains why it cannot.
+    pub fn parse_with(
+        input: &str,
+        mut cell: impl FnMut(char) -> std::result::Result<T, String>,
+    ) -> Result<Self> {
+        let mut cells = Vec::new();
+        let mut rows = 0;
+        let mut columns = 0;
+
+        for (row, line) in input.lines().enumerate() {
+            let mut length = 0;
+            for (column, character) in line.chars().enumerate() {
+                cells.push(cell(character).map_err(|e| Error::parse(row + 1, column + 1, e))?);
+                length += 1;
+            }
+            if row > 0 && length != columns {
+                return Err(Error::parse(
+                    row + 1,
+                    usize::min(length, columns) + 1,
+                    format!("expected {} columns but got {}", columns, length),
+                ));
+            }
+            rows += 1;
+            columns = length;
+        }
+
+        Ok(Grid {
+            cells,
+            rows,
+            columns,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, (row, column): Position) -> bool {
+        row < self.rows && column < self.columns
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.index_of(position).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.index_of(position).map(|index| &mut self.cells[index])
+    }
+
+    fn index_of(&self, position: Position) -> Option<usize> {
+        self.contains(position)
+            .then_some(position.0 * self.columns + position.1)
+    }
+
+    /// The cells of `row`, panics if there is no such row.
+    pub fn row(&self, row: usize) -> &[T] {
+        assert!(row < self.rows, "row {} is out of bounds", row);
+        &self.cells[row * self.columns..(row + 1) * self.columns]
+    }
+
+    /// The cells of `column` from top to bottom, panics if there is no such column.
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> + '_ {
+        assert!(column < self.columns, "column {} is out of bounds", column);
+        self.cells.iter().skip(column).step_by(self.columns)
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let columns = self.columns;
+        (0..self.rows).flat_map(move |row| (0..columns).map(move |column| (row, column)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> + '_ {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn values(&self) -> slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    /// The position `delta` rows and columns away, `None` if that is off the grid.
+    pub fn offset(&self, (row, column): Position, delta: (isize, isize)) -> Option<Position> {
+        let position = (
+            row.checked_add_signed(delta.0)?,
+            column.checked_add_signed(delta.1)?,
+        );
+        self.contains(position).then_some(position)
+    }
+
+    /// The horizontal and vertical neighbours of `position` that are on the grid.
+    pub fn neighbours4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        NEIGHBOURS_4
+            .iter()
+            .filter_map(move |&delta| self.offset(position, delta))
+    }
+
+    /// Like [`Grid::neighbours4`], including the diagonal neighbours.
+    pub fn neighbours8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        NEIGHBOURS_8
+            .iter()
+            .filter_map(move |&delta| self.offset(position, delta))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            rows: self.rows,
+            columns: self.columns,
+        }
+    }
+}
+
+impl Grid<u8> {
+    /// Reads a map of single digits, e.g. a height map.
+    pub fn parse_digits(input: &str) -> Result<Self> {
+        Grid::parse_with(input, |character| {
+            character
+                .to_digit(10)
+                .map(|digit| digit as u8)
+                .ok_or_else(|| format!("expected a digit but got {:?}", character))
+        })
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        match self.index_of(position) {
+            Some(index) => &self.cells[index],
+            None => panic!(
+                "{:?} is outside of the {}x{} grid",
+                position, self.rows, self.columns
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        match self.index_of(position) {
+            Some(index) => &mut self.cells[index],
+            None => panic!(
+                "{:?} is outside of the {}x{} grid",
+                position, self.rows, self.columns
+            ),
+        }
+    }
+}
+
+/// Renders one line per row, with the cells next to each other.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in 0..self.rows {
+            for cell in self.row(row) {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_neighbours() {
+    let grid = Grid::new(3, 4, 0);
+
+    assert_eq!(
+        grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+        [(0, 1), (1, 0)]
+    );
+    assert_eq!(
+        grid.neighbours8((2, 3)).collect::<Vec<_>>(),
+        [(1, 2), (1, 3), (2, 2)]
+    );
+    assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+    assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+}
+
+#[test]
+fn test_parse_digits() {
+    let grid = Grid::parse_digits("219\n398\n").unwrap();
+
+    assert_eq!(grid.row(1), [3, 9, 8]);
+    assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), [9, 8]);
+    assert_eq!(grid.to_string(), "219\n398\n");
+
+    let error = Grid::parse_digits("219\n39").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 2, column 3: expected 3 columns but got 2"
+    );
+}
@@ -0,0 +1,178 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use aoc_core::Part;
+
+use crate::Grid;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    /// Mixes `self` with `other`, `amount` 0 is `self` and 1 is `other`.
+    pub fn mix(self, other: Rgb, amount: f64) -> Rgb {
+        let amount = amount.clamp(0.0, 1.0);
+        let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * amount).round() as u8;
+        Rgb(
+            channel(self.0, other.0),
+            channel(self.1, other.1),
+            channel(self.2, other.2),
+        )
+    }
+
+    fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Colours from dark blue over red to yellow, for counts and heights from 0 to `max`.
+pub fn scale(value: usize, max: usize) -> Rgb {
+    const STOPS: [Rgb; 3] = [Rgb(20, 20, 90), Rgb(210, 50, 60), Rgb(250, 230, 90)];
+
+    let position = match max {
+        0 => 0.0,
+        _ => value.min(max) as f64 / max as f64 * (STOPS.len() - 1) as f64,
+    };
+    let stop = (position as usize).min(STOPS.len() - 2);
+    STOPS[stop].mix(STOPS[stop + 1], position - stop as f64)
+}
+
+/// A distinct colour for every `index`, e.g. to tell regions apart.
+pub fn palette(index: usize) -> Rgb {
+    // stepping around the colour wheel by the golden ratio keeps neighbours apart
+    let hue = (index as f64 * 0.618_033_988_75).fract() * 6.0;
+    let (saturation, value) = (0.65, 0.95);
+
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as usize {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |c: f64| ((c + value - chroma) * 255.0).round() as u8;
+    Rgb(channel(r), channel(g), channel(b))
+}
+
+/// Days that can draw their puzzle as an image.
+pub trait Render {
+    /// Draws what `part` is answered from, one pixel per cell.
+    fn render(&self, part: Part) -> Grid<Rgb>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Png,
+    Ppm,
+    Svg,
+}
+
+impl Format {
+    /// The format matching the extension of `path`.
+    pub fn from_path(path: &Path) -> Option<Format> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "png" => Some(Format::Png),
+            "ppm" => Some(Format::Ppm),
+            "svg" => Some(Format::Svg),
+            _ => None,
+        }
+    }
+}
+
+/// Writes `image` to `path` in the format its extension asks for, one pixel per cell.
+pub fn save(image: &Grid<Rgb>, path: &Path) -> io::Result<()> {
+    let format = Format::from_path(path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "unsupported image format, use .png, .ppm or .svg",
+        )
+    })?;
+    let mut writer = BufWriter::new(File::create(path)?);
+    write(image, format, &mut writer)?;
+    writer.flush()
+}
+
+pub fn write(image: &Grid<Rgb>, format: Format, writer: impl Write) -> io::Result<()> {
+    match format {
+        Format::Png => write_png(image, writer),
+        Format::Ppm => write_ppm(image, writer),
+        Format::Svg => write_svg(image, writer),
+    }
+}
+
+fn write_png(image: &Grid<Rgb>, writer: impl Write) -> io::Result<()> {
+    let mut encoder = png::Encoder::new(writer, image.columns() as u32, image.rows() as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let data: Vec<u8> = image.values().flat_map(|c| [c.0, c.1, c.2]).collect();
+    encoder
+        .write_header()
+        .and_then(|mut png| png.write_image_data(&data))
+        .map_err(io::Error::other)
+}
+
+fn write_ppm(image: &Grid<Rgb>, mut writer: impl Write) -> io::Result<()> {
+    write!(writer, "P6\n{} {}\n255\n", image.columns(), image.rows())?;
+    let data: Vec<u8> = image.values().flat_map(|c| [c.0, c.1, c.2]).collect();
+    writer.write_all(&data)
+}
+
+fn write_svg(image: &Grid<Rgb>, mut writer: impl Write) -> io::Result<()> {
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {0} {1}" width="{0}" height="{1}" shape-rendering="crispEdges">"#,
+        image.columns(),
+        image.rows()
+    )?;
+    for row in 0..image.rows() {
+        // one rectangle per run of equally coloured cells keeps large images small
+        let cells = image.row(row);
+        let mut start = 0;
+        while start < cells.len() {
+            let length = cells[start..]
+                .iter()
+                .take_while(|&&c| c == cells[start])
+                .count();
+            writeln!(
+                writer,
+                r#"<rect x="{}" y="{}" width="{}" height="1" fill="{}"/>"#,
+                start,
+                row,
+                length,
+                cells[start].hex()
+            )?;
+            start += length;
+        }
+    }
+    writeln!(writer, "</svg>")
+}
+
+#[test]
+fn test_write_ppm() {
+    let image = Grid::from_fn(1, 2, |(_, column)| palette(column));
+    let mut ppm = Vec::new();
+
+    write(&image, Format::Ppm, &mut ppm).unwrap();
+
+    let (first, second) = (palette(0), palette(1));
+    assert_ne!(first, second);
+    assert_eq!(
+        ppm,
+        [
+            b"P6\n2 1\n255\n".as_slice(),
+            &[first.0, first.1, first.2, second.0, second.1, second.2]
+        ]
+        .concat()
+    );
+}
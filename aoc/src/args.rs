@@ -0,0 +1,394 @@
+use std::path::Path;
+
+use aoc_2021_120101::Aggregate;
+use aoc_2021_120401::WinPattern;
+use aoc_core::Part;
+use aoc_grid::Format;
+
+use crate::{
+    days::{DAYS, RENDERABLE},
+    inputs::Variant,
+};
+
+pub(crate) const USAGE: &str = "Usage: aoc run <day> [--part <1|2>] [--render <file.png|ppm|svg>] \
+                                [--window <readings>] [--aggregate <sum|mean|max>] [--lenient] [--stats <text|json>] \
+                                [--trajectory <csv|json|plot>] [--ranking] [--analyze <games> [--seed <number>]] [--adversary <board>] \
+                                [--pattern <rows|columns|diagonals|corners|x|blackout>]... [--masks <file>]... \
+                                [--bench <iterations> [--baseline <file>] [--save-baseline]] \
+                                [<input|-> | [--example] [--inputs <dir>]]
+       aoc gen <day> [--seed <number>] [--size <number>]";
+
+#[derive(Clone, Debug)]
+pub(crate) struct BenchArgs {
+    pub(crate) iterations: usize,
+    /// Defaults to `<input>.bench`.
+    pub(crate) baseline: Option<String>,
+    /// Overwrites an existing baseline with this run's timings.
+    pub(crate) save: bool,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct AnalyzeArgs {
+    pub(crate) games: usize,
+    pub(crate) seed: u64,
+}
+
+/// How `--stats` prints its report.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Output {
+    Text,
+    Json,
+}
+
+/// How `--trajectory` exports the course.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Export {
+    Csv,
+    Json,
+    Plot,
+}
+
+/// What `aoc run` does with the input, answering the puzzle unless another mode is given.
+#[derive(Clone, Debug)]
+pub(crate) enum Mode {
+    Answer,
+    Bench(BenchArgs),
+    /// Day 1 only, reports statistics on the depth readings.
+    Stats(Output),
+    /// Day 2 only, exports where the submarines are after each command.
+    Trajectory(Export),
+    /// Day 4 only, prints every win in the order the boards finish.
+    Ranking,
+    /// Day 4 only, the odds of the boards over games with random draws.
+    Analyze(AnalyzeArgs),
+    /// Day 4 only, draw orders that make this board win first and last.
+    Adversary(usize),
+}
+
+impl Mode {
+    /// The option that selects this mode.
+    fn option(&self) -> &'static str {
+        match self {
+            Mode::Answer => "",
+            Mode::Bench(_) => "--bench",
+            Mode::Stats(_) => "--stats",
+            Mode::Trajectory(_) => "--trajectory",
+            Mode::Ranking => "--ranking",
+            Mode::Analyze(_) => "--analyze",
+            Mode::Adversary(_) => "--adversary",
+        }
+    }
+}
+
+/// The options that only one day has, with that day.
+const DAY_OPTIONS: [(&str, u8); 10] = [
+    ("--window", 1),
+    ("--aggregate", 1),
+    ("--lenient", 1),
+    ("--stats", 1),
+    ("--trajectory", 2),
+    ("--pattern", 4),
+    ("--masks", 4),
+    ("--ranking", 4),
+    ("--analyze", 4),
+    ("--adversary", 4),
+];
+
+/// A way to win given with `--pattern` or `--masks`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Pattern {
+    Builtin(WinPattern),
+    /// A file of masks, see [`WinPattern::masks`].
+    Masks(String),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Input {
+    /// A file named on the command line, `-` is stdin.
+    Path(String),
+    /// The day's own file, found in the inputs directory if given or the default one.
+    Find {
+        variant: Variant,
+        inputs: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct RunArgs {
+    pub(crate) day: u8,
+    pub(crate) parts: Vec<Part>,
+    pub(crate) input: Input,
+    pub(crate) mode: Mode,
+    /// Image file to draw the (last) requested part to.
+    pub(crate) render: Option<String>,
+    /// Day 1 only, the window part 2 slides over the depth readings.
+    pub(crate) window: Option<usize>,
+    /// Day 1 only, how part 2 combines the readings of a window.
+    pub(crate) aggregate: Option<Aggregate>,
+    /// Day 1 only, skips what is not a depth instead of failing.
+    pub(crate) lenient: bool,
+    /// Day 4 only, how boards win instead of completing a row or column, any of them will do.
+    pub(crate) patterns: Vec<Pattern>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct GenArgs {
+    pub(crate) day: u8,
+    pub(crate) seed: u64,
+    /// Defaults to the day's own default size.
+    pub(crate) size: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Command {
+    Run(RunArgs),
+    Gen(GenArgs),
+}
+
+impl Command {
+    pub(crate) fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        match args.next().as_deref() {
+            Some("run") => Self::run_args(args).map(Command::Run),
+            Some("gen") => Self::gen_args(args).map(Command::Gen),
+            Some(other) => Err(format!("Unknown command {:?}", other)),
+            None => Err(String::from("Missing command")),
+        }
+    }
+
+    fn gen_args(mut args: impl Iterator<Item = String>) -> Result<GenArgs, String> {
+        let mut day = None;
+        let mut seed = 0;
+        let mut size = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => seed = Self::number(args.next(), "seed")?,
+                "--size" => size = Some(Self::number(args.next(), "size")?),
+                _ if day.is_none() => day = Some(Self::day(&arg)?),
+                _ => return Err(format!("Unexpected argument {:?}", arg)),
+            }
+        }
+
+        Ok(GenArgs {
+            day: day.ok_or("Missing day")?,
+            seed,
+            size,
+        })
+    }
+
+    fn number<T: std::str::FromStr>(arg: Option<String>, name: &str) -> Result<T, String> {
+        let arg = arg.ok_or_else(|| format!("Missing {}", name))?;
+        arg.parse()
+            .map_err(|_| format!("Invalid {} {:?}", name, arg))
+    }
+
+    fn day(arg: &str) -> Result<u8, String> {
+        let day = arg
+            .parse::<u8>()
+            .map_err(|_| format!("Invalid day {:?}", arg))?;
+        if !DAYS.contains(&day) {
+            return Err(format!("There is no solution for day {}", day));
+        }
+        Ok(day)
+    }
+
+    fn run_args(mut args: impl Iterator<Item = String>) -> Result<RunArgs, String> {
+        let mut day = None;
+        let mut parts = Part::ALL.to_vec();
+        let mut input = None;
+        let mut modes = Vec::new();
+        let mut options = Vec::new();
+        let mut baseline = None;
+        let mut save_baseline = false;
+        let mut render = None;
+        let mut variant = None;
+        let mut inputs = None;
+        let mut window = None;
+        let mut aggregate = None;
+        let mut lenient = false;
+        let mut patterns = Vec::new();
+        let mut seed = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--part" => {
+                    parts = match args.next().as_deref() {
+                        Some("1") => vec![Part::One],
+                        Some("2") => vec![Part::Two],
+                        Some(other) => return Err(format!("Invalid part {:?}", other)),
+                        None => return Err(String::from("Missing part")),
+                    }
+                }
+                "--bench" => {
+                    let iterations = match args.next() {
+                        Some(arg) => match arg.parse::<usize>() {
+                            Ok(iterations) if iterations > 0 => iterations,
+                            _ => return Err(format!("Invalid number of iterations {:?}", arg)),
+                        },
+                        None => return Err(String::from("Missing number of iterations")),
+                    };
+                    modes.push(Mode::Bench(BenchArgs {
+                        iterations,
+                        baseline: None,
+                        save: false,
+                    }))
+                }
+                "--window" => {
+                    options.push("--window");
+                    window = match Self::number(args.next(), "window")? {
+                        0 => return Err(String::from("A window needs at least one reading")),
+                        readings => Some(readings),
+                    }
+                }
+                "--aggregate" => {
+                    options.push("--aggregate");
+                    let arg = args.next().ok_or("Missing aggregate")?;
+                    aggregate = Some(
+                        arg.parse()
+                            .map_err(|e| format!("Invalid aggregate: {}", e))?,
+                    )
+                }
+                "--lenient" => {
+                    options.push("--lenient");
+                    lenient = true
+                }
+                "--stats" => modes.push(Mode::Stats(match args.next().as_deref() {
+                    Some("text") => Output::Text,
+                    Some("json") => Output::Json,
+                    Some(other) => return Err(format!("Invalid stats format {:?}", other)),
+                    None => return Err(String::from("Missing stats format")),
+                })),
+                "--trajectory" => modes.push(Mode::Trajectory(match args.next().as_deref() {
+                    Some("csv") => Export::Csv,
+                    Some("json") => Export::Json,
+                    Some("plot") => Export::Plot,
+                    Some(other) => return Err(format!("Invalid trajectory format {:?}", other)),
+                    None => return Err(String::from("Missing trajectory format")),
+                })),
+                "--ranking" => modes.push(Mode::Ranking),
+                "--analyze" => {
+                    let games = match Self::number(args.next(), "number of games")? {
+                        0 => return Err(String::from("An analysis needs at least one game")),
+                        games => games,
+                    };
+                    modes.push(Mode::Analyze(AnalyzeArgs { games, seed: 0 }))
+                }
+                "--seed" => seed = Some(Self::number(args.next(), "seed")?),
+                "--adversary" => modes.push(Mode::Adversary(Self::number(args.next(), "board")?)),
+                "--pattern" => {
+                    options.push("--pattern");
+                    let arg = args.next().ok_or("Missing win pattern")?;
+                    patterns.push(Pattern::Builtin(
+                        arg.parse()
+                            .map_err(|e| format!("Invalid win pattern: {}", e))?,
+                    ))
+                }
+                "--masks" => {
+                    options.push("--masks");
+                    patterns.push(Pattern::Masks(args.next().ok_or("Missing masks file")?))
+                }
+                "--example" => variant = Some(Variant::Example),
+                "--inputs" => inputs = Some(args.next().ok_or("Missing inputs directory")?),
+                "--render" => render = Some(args.next().ok_or("Missing image file")?),
+                "--baseline" => baseline = Some(args.next().ok_or("Missing baseline file")?),
+                "--save-baseline" => save_baseline = true,
+                _ if day.is_none() => day = Some(Self::day(&arg)?),
+                _ if input.is_none() => input = Some(arg),
+                _ => return Err(format!("Unexpected argument {:?}", arg)),
+            }
+        }
+
+        let day = day.ok_or("Missing day")?;
+
+        let mut mode = match modes.len() {
+            0 => Mode::Answer,
+            1 => modes.remove(0),
+            _ => {
+                return Err(format!(
+                    "{} cannot be combined with {}",
+                    modes[1].option(),
+                    modes[0].option()
+                ))
+            }
+        };
+
+        let mut given = options.clone();
+        given.push(mode.option());
+        if let Some((option, only)) = DAY_OPTIONS
+            .iter()
+            .find(|(option, only)| day != *only && given.contains(option))
+        {
+            return Err(format!("{} is for day {} only", option, only));
+        }
+
+        if let Some(render) = &render {
+            if !RENDERABLE.contains(&day) {
+                return Err(format!("Day {} cannot be rendered", day));
+            }
+            if Format::from_path(Path::new(render)).is_none() {
+                return Err(format!(
+                    "Unsupported image format {:?}, use .png, .ppm or .svg",
+                    render
+                ));
+            }
+            if !matches!(mode, Mode::Answer) {
+                return Err(format!(
+                    "--render cannot be combined with {}",
+                    mode.option()
+                ));
+            }
+        }
+        if let Some(option) = options.first() {
+            if render.is_some() || matches!(mode, Mode::Bench(_)) {
+                return Err(format!(
+                    "{} cannot be combined with --bench or --render",
+                    option
+                ));
+            }
+        }
+
+        match (&mut mode, seed) {
+            (Mode::Analyze(analyze), Some(seed)) => analyze.seed = seed,
+            (_, Some(_)) => return Err(String::from("--seed needs --analyze")),
+            (_, None) => (),
+        }
+
+        let input = match (input, variant, inputs) {
+            (Some(path), None, None) => Input::Path(path),
+            (None, variant, inputs) => Input::Find {
+                variant: variant.unwrap_or(Variant::Input),
+                inputs,
+            },
+            (Some(_), _, _) => {
+                return Err(String::from(
+                    "--example and --inputs cannot be combined with an input file",
+                ))
+            }
+        };
+        match (&mut mode, baseline) {
+            (Mode::Bench(_), None) if matches!(&input, Input::Path(path) if path == "-") => {
+                return Err(String::from("--bench on stdin needs a --baseline file"))
+            }
+            (Mode::Bench(bench), baseline) => bench.baseline = baseline,
+            (_, Some(_)) => return Err(String::from("--baseline needs --bench")),
+            (_, None) => (),
+        }
+        match &mut mode {
+            Mode::Bench(bench) => bench.save = save_baseline,
+            _ if save_baseline => return Err(String::from("--save-baseline needs --bench")),
+            _ => (),
+        }
+
+        Ok(RunArgs {
+            day,
+            parts,
+            input,
+            mode,
+            render,
+            window,
+            aggregate,
+            lenient,
+            patterns,
+        })
+    }
+}
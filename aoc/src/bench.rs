@@ -0,0 +1,204 @@
+use std::{
+    fmt, fs,
+    hint::black_box,
+    io,
+    iter::once,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use aoc_core::{Part, Result, Solution};
+
+/// A median that is this much slower than the baseline's counts as a regression.
+const REGRESSION_THRESHOLD: f64 = 0.1;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Phase {
+    Parse,
+    Part(Part),
+}
+
+impl Phase {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "parse" => Some(Phase::Parse),
+            "part 1" => Some(Phase::Part(Part::One)),
+            "part 2" => Some(Phase::Part(Part::Two)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Part(part) => write!(f, "part {}", part),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Timing {
+    phase: Phase,
+    min: Duration,
+    median: Duration,
+    p95: Duration,
+}
+
+impl Timing {
+    fn new(phase: Phase, mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        // nearest-rank percentile
+        let percentile = |p: usize| samples[(samples.len() * p).div_ceil(100).max(1) - 1];
+
+        Timing {
+            phase,
+            min: samples[0],
+            median: percentile(50),
+            p95: percentile(95),
+        }
+    }
+
+    /// How much slower (positive) or faster (negative) the median is than `baseline`'s,
+    /// `None` if the baseline's median is too short to compare against.
+    fn change(&self, baseline: &Timing) -> Option<f64> {
+        (!baseline.median.is_zero())
+            .then(|| self.median.as_secs_f64() / baseline.median.as_secs_f64() - 1.0)
+    }
+}
+
+/// Parses `input` and answers `parts` `iterations` times, timing every phase on its own.
+pub(crate) fn measure<S: Solution>(
+    input: &str,
+    parts: &[Part],
+    iterations: usize,
+) -> Result<Vec<Timing>> {
+    let phases: Vec<Phase> = once(Phase::Parse)
+        .chain(parts.iter().map(|&part| Phase::Part(part)))
+        .collect();
+    let mut samples = vec![Vec::with_capacity(iterations); phases.len()];
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let solution = black_box(S::parse(input)?);
+        samples[0].push(start.elapsed());
+
+        for (&part, samples) in parts.iter().zip(&mut samples[1..]) {
+            let start = Instant::now();
+            black_box(match part {
+                Part::One => solution.part1()?,
+                Part::Two => solution.part2()?,
+            });
+            samples.push(start.elapsed());
+        }
+    }
+
+    Ok(phases
+        .into_iter()
+        .zip(samples)
+        .map(|(phase, samples)| Timing::new(phase, samples))
+        .collect())
+}
+
+/// Reads a baseline written by [`save_baseline`], `None` if there is none yet.
+pub(crate) fn load_baseline(path: &Path) -> std::result::Result<Option<Vec<Timing>>, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    content
+        .lines()
+        .enumerate()
+        .map(|(n, line)| {
+            let invalid = || format!("line {}: invalid baseline entry {:?}", n + 1, line);
+            let columns: Vec<&str> = line.split('\t').collect();
+            let (phase, nanos) = match columns[..] {
+                [phase, min, median, p95] => (phase, [min, median, p95]),
+                _ => return Err(invalid()),
+            };
+            let phase = Phase::from_name(phase).ok_or_else(invalid)?;
+            let [min, median, p95] = nanos.map(|nanos| nanos.parse().map(Duration::from_nanos));
+
+            Ok(Timing {
+                phase,
+                min: min.map_err(|_| invalid())?,
+                median: median.map_err(|_| invalid())?,
+                p95: p95.map_err(|_| invalid())?,
+            })
+        })
+        .collect::<std::result::Result<_, _>>()
+        .map(Some)
+}
+
+/// Writes one line per phase: its name, then min, median and p95 in nanoseconds, separated by tabs.
+pub(crate) fn save_baseline(path: &Path, timings: &[Timing]) -> io::Result<()> {
+    let content: String = timings
+        .iter()
+        .map(|timing| {
+            format!(
+                "{}\t{}\t{}\t{}\n",
+                timing.phase,
+                timing.min.as_nanos(),
+                timing.median.as_nanos(),
+                timing.p95.as_nanos()
+            )
+        })
+        .collect();
+
+    fs::write(path, content)
+}
+
+/// Prints `timings` next to their change against `baseline`, returns the phases that regressed.
+pub(crate) fn report(timings: &[Timing], baseline: Option<&[Timing]>) -> Vec<Phase> {
+    let mut regressions = Vec::new();
+
+    println!("{:<8}{:>12}{:>12}{:>12}", "phase", "min", "median", "p95");
+    for timing in timings {
+        print!(
+            "{:<8}{:>12.2?}{:>12.2?}{:>12.2?}",
+            timing.phase.to_string(),
+            timing.min,
+            timing.median,
+            timing.p95
+        );
+
+        let previous = baseline.and_then(|baseline| {
+            baseline
+                .iter()
+                .find(|previous| previous.phase == timing.phase)
+        });
+        match previous.map(|previous| timing.change(previous)) {
+            Some(Some(change)) if change > REGRESSION_THRESHOLD => {
+                println!("  {:+.1}% regression", change * 100.0);
+                regressions.push(timing.phase);
+            }
+            Some(Some(change)) => println!("  {:+.1}%", change * 100.0),
+            Some(None) => println!("  (baseline median is 0)"),
+            None => println!(),
+        }
+    }
+
+    regressions
+}
+
+#[test]
+fn test_timing_percentiles() {
+    let samples = (1..=20).rev().map(Duration::from_millis).collect();
+
+    let timing = Timing::new(Phase::Parse, samples);
+
+    assert_eq!(timing.min, Duration::from_millis(1));
+    assert_eq!(timing.median, Duration::from_millis(10));
+    assert_eq!(timing.p95, Duration::from_millis(19));
+}
+
+#[test]
+fn test_change_against_a_zero_baseline() {
+    let timing = |millis| Timing::new(Phase::Parse, vec![Duration::from_millis(millis)]);
+
+    assert_eq!(timing(3).change(&timing(2)), Some(0.5));
+    assert_eq!(timing(3).change(&timing(0)), None);
+}
@@ -0,0 +1,198 @@
+use aoc_2021_120401::WinPattern;
+use aoc_core::{normalize, Answer, Error, Generate, Part, Result, Rng, Solution};
+use aoc_grid::{Grid, Render, Rgb};
+
+use crate::{
+    args::{Pattern, RunArgs},
+    bench::{self, Timing},
+};
+
+pub(crate) const DAYS: [u8; 10] = [1, 2, 3, 4, 5, 6, 7, 9, 20, 21];
+
+/// The `problem NN` directory holding the solution of `day`, day 9 solves both parts in problem 2.
+pub(crate) fn problem(day: u8) -> u8 {
+    match day {
+        9 => 2,
+        _ => 1,
+    }
+}
+
+/// Days that can draw themselves with `--render`.
+pub(crate) const RENDERABLE: [u8; 3] = [5, 9, 20];
+
+/// Evaluates `$body` with `$S` naming the solution type of `$day`, which has to be one of [`DAYS`].
+macro_rules! with_solution {
+    ($day:expr, $S:ident => $body:expr) => {
+        match $day {
+            1 => {
+                type $S = aoc_2021_120101::Simulation;
+                $body
+            }
+            2 => {
+                type $S = aoc_2021_120201::Simulation;
+                $body
+            }
+            3 => {
+                type $S = aoc_2021_120301::Report;
+                $body
+            }
+            4 => {
+                type $S = aoc_2021_120401::Bingo;
+                $body
+            }
+            5 => {
+                type $S = aoc_2021_120501::Simulation;
+                $body
+            }
+            6 => {
+                type $S = aoc_2021_120601::Simulation;
+                $body
+            }
+            7 => {
+                type $S = aoc_2021_120701::Simulation;
+                $body
+            }
+            9 => {
+                type $S = aoc_2021_120902::Simulation;
+                $body
+            }
+            20 => {
+                type $S = aoc_2021_122001::Simulation;
+                $body
+            }
+            21 => {
+                type $S = aoc_2021_122101::Simulation;
+                $body
+            }
+            day => unreachable!("there is no solution for day {}", day),
+        }
+    };
+}
+
+fn solve<S: Solution>(input: &str, parts: &[Part]) -> Result<Answer> {
+    answer(&S::parse(input)?, parts)
+}
+
+fn answer<S: Solution>(solution: &S, parts: &[Part]) -> Result<Answer> {
+    match parts {
+        [Part::One] => Ok(Answer::new(solution.part1()?, None)),
+        [Part::Two] => Ok(Answer::new(None, solution.part2()?)),
+        _ => solution.answer(),
+    }
+}
+
+/// Day 1 set up with the options in `args`.
+fn sonar(args: &RunArgs, input: &str) -> Result<aoc_2021_120101::Simulation> {
+    let mut sonar = match args.lenient {
+        true => aoc_2021_120101::Simulation::lenient(normalize(input))?,
+        false => aoc_2021_120101::Simulation::parse(input)?,
+    };
+    if let Some(window) = args.window {
+        sonar = sonar.with_window(window);
+    }
+    if let Some(aggregate) = args.aggregate {
+        sonar = sonar.with_aggregate(aggregate);
+    }
+    Ok(sonar)
+}
+
+/// Day 4 set up with the win patterns in `args`.
+fn bingo(args: &RunArgs, input: &str) -> Result<aoc_2021_120401::Bingo> {
+    let bingo = aoc_2021_120401::Bingo::parse(input)?;
+    if args.patterns.is_empty() {
+        return Ok(bingo);
+    }
+
+    let mut patterns = Vec::new();
+    for pattern in &args.patterns {
+        match pattern {
+            Pattern::Builtin(pattern) => patterns.push(pattern.clone()),
+            Pattern::Masks(path) => {
+                let masks = aoc_core::load(path)
+                    .map_err(Error::from)
+                    .and_then(|masks| WinPattern::masks(&normalize(&masks)))
+                    .map_err(|e| Error::invalid(format!("{}: {}", path, e)))?;
+                patterns.extend(masks);
+            }
+        }
+    }
+    bingo.with_patterns(patterns)
+}
+
+/// Runs the requested parts of the day in `args`.
+pub(crate) fn run(args: &RunArgs, input: &str) -> Result<Answer> {
+    match args.day {
+        1 if args.window.is_some() || args.aggregate.is_some() || args.lenient => {
+            answer(&sonar(args, input)?, &args.parts)
+        }
+        4 if !args.patterns.is_empty() => answer(&bingo(args, input)?, &args.parts),
+        day => with_solution!(day, S => solve::<S>(input, &args.parts)),
+    }
+}
+
+/// Statistics on the depth readings of day 1.
+pub(crate) fn stats(args: &RunArgs, input: &str) -> Result<aoc_2021_120101::Stats> {
+    Ok(sonar(args, input)?.stats())
+}
+
+/// Where the submarines of day 2 are after each command.
+pub(crate) fn trajectory(input: &str) -> Result<aoc_2021_120201::Trajectory> {
+    aoc_2021_120201::Simulation::parse(input)?.trajectory()
+}
+
+/// Every win of the day 4 bingo game, in the order the boards finish.
+pub(crate) fn ranking(args: &RunArgs, input: &str) -> Result<aoc_2021_120401::Ranking> {
+    Ok(bingo(args, input)?.ranking())
+}
+
+/// The odds of the day 4 boards over `games` games with random draws.
+pub(crate) fn analyze(
+    args: &RunArgs,
+    input: &str,
+    games: usize,
+    seed: u64,
+) -> Result<aoc_2021_120401::Analysis> {
+    bingo(args, input)?.analyze(games, seed)
+}
+
+/// Draw orders that make day 4 board `board` win first and last.
+pub(crate) fn adversary(
+    args: &RunArgs,
+    input: &str,
+    board: usize,
+) -> Result<aoc_2021_120401::Witnesses> {
+    bingo(args, input)?.witnesses(board)
+}
+
+fn generate<S: Generate>(seed: u64, size: Option<usize>) -> String {
+    S::generate(&mut Rng::new(seed), size.unwrap_or(S::DEFAULT_SIZE))
+}
+
+/// Makes up puzzle input for `day`, the same `seed` and `size` always give the same input.
+pub(crate) fn gen(day: u8, seed: u64, size: Option<usize>) -> String {
+    with_solution!(day, S => generate::<S>(seed, size))
+}
+
+/// Times parsing and the requested parts of `day` over `iterations` runs.
+pub(crate) fn bench(
+    day: u8,
+    input: &str,
+    parts: &[Part],
+    iterations: usize,
+) -> Result<Vec<Timing>> {
+    with_solution!(day, S => bench::measure::<S>(input, parts, iterations))
+}
+
+fn draw<S: Solution + Render>(input: &str, part: Part) -> Result<Grid<Rgb>> {
+    Ok(S::parse(input)?.render(part))
+}
+
+/// Draws what `part` of `day` is answered from, `day` has to be one of [`RENDERABLE`].
+pub(crate) fn render(day: u8, input: &str, part: Part) -> Result<Grid<Rgb>> {
+    match day {
+        5 => draw::<aoc_2021_120501::Simulation>(input, part),
+        9 => draw::<aoc_2021_120902::Simulation>(input, part),
+        20 => draw::<aoc_2021_122001::Simulation>(input, part),
+        _ => unreachable!("day {} cannot be rendered", day),
+    }
+}
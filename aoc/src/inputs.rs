@@ -0,0 +1,175 @@
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Variant {
+    Example,
+    Input,
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Variant::Example => write!(f, "example"),
+            Variant::Input => write!(f, "input"),
+        }
+    }
+}
+
+/// The inputs directory to search, `dir` if given, then `AOC_INPUTS`, then this repository.
+pub(crate) fn directory(dir: Option<&str>) -> PathBuf {
+    match dir {
+        Some(dir) => PathBuf::from(dir),
+        None => env::var_os("AOC_INPUTS")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+                crate_dir.parent().unwrap_or(crate_dir).to_path_buf()
+            }),
+    }
+}
+
+/// Finds `variant` of the puzzle input for `problem` of `day` in `inputs`.
+///
+/// Looks at `day 05/rust/problem 01/input.txt` as laid out in this repository, in
+/// the crate directories below that (like day 3 has them) by name and at `day05/input.txt`,
+/// each of these may also be gzip-compressed as `input.txt.gz`. The first one found wins.
+pub(crate) fn find(
+    inputs: &Path,
+    day: u8,
+    problem: u8,
+    variant: Variant,
+) -> Result<PathBuf, String> {
+    let problem_dir = inputs
+        .join(format!("day {:02}", day))
+        .join("rust")
+        .join(format!("problem {:02}", problem));
+    // read_dir lists the entries in no particular order
+    let mut crate_dirs: Vec<PathBuf> = fs::read_dir(&problem_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    crate_dirs.sort();
+    let dirs: Vec<PathBuf> = [problem_dir.clone()]
+        .into_iter()
+        .chain(crate_dirs)
+        .chain([inputs.join(format!("day{:02}", day))])
+        .collect();
+
+    let names = [format!("{}.txt", variant), format!("{}.txt.gz", variant)];
+    let found = dirs
+        .iter()
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|path| path.is_file());
+
+    found.ok_or_else(|| {
+        format!(
+            "There is no {} for day {} in {}, looked for {} in {:?}, its crate directories and {:?}",
+            variant,
+            day,
+            inputs.display(),
+            names.join(" or "),
+            problem_dir.strip_prefix(inputs).unwrap_or(&problem_dir),
+            format!("day{:02}", day),
+        )
+    })
+}
+
+/// Input files under the temporary directory for one test, removed again when the test ends,
+/// also when it fails.
+#[cfg(test)]
+struct TestDir(PathBuf);
+
+#[cfg(test)]
+impl TestDir {
+    fn new(name: &str, files: &[&str]) -> Self {
+        let dir =
+            TestDir(env::temp_dir().join(format!("aoc-inputs-{}-{}", std::process::id(), name)));
+        for file in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, file).unwrap();
+        }
+        dir
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for TestDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn test_find_in_both_layouts() {
+    let dir = TestDir::new(
+        "layouts",
+        &[
+            "day 03/rust/problem 01/b/input.txt",
+            "day 03/rust/problem 01/a/input.txt.gz",
+            "day 03/rust/problem 01/a/example.txt",
+            "day03/example.txt",
+            "day 05/rust/problem 01/input.txt",
+            "day05/input.txt",
+            "day06/input.txt.gz",
+        ],
+    );
+    let find = |day, variant| {
+        find(&dir, day, 1, variant).map(|path| path.strip_prefix(&*dir).unwrap().to_path_buf())
+    };
+
+    // crate directories by name, uncompressed before compressed in each directory
+    assert_eq!(
+        find(3, Variant::Input),
+        Ok(PathBuf::from("day 03/rust/problem 01/a/input.txt.gz"))
+    );
+    assert_eq!(
+        find(3, Variant::Example),
+        Ok(PathBuf::from("day 03/rust/problem 01/a/example.txt"))
+    );
+    assert_eq!(
+        find(5, Variant::Input),
+        Ok(PathBuf::from("day 05/rust/problem 01/input.txt"))
+    );
+    assert_eq!(
+        find(6, Variant::Input),
+        Ok(PathBuf::from("day06/input.txt.gz"))
+    );
+    assert!(find(6, Variant::Example)
+        .unwrap_err()
+        .starts_with("There is no example for day 6 in "));
+}
+
+#[test]
+fn test_inputs_option_before_environment() {
+    let environment = TestDir::new("environment", &["day07/input.txt"]);
+    let option = TestDir::new("option", &["day07/input.txt"]);
+    env::set_var("AOC_INPUTS", &*environment);
+
+    let option_arg = option.to_str().unwrap();
+    assert_eq!(
+        find(&directory(None), 7, 1, Variant::Input),
+        Ok(environment.join("day07/input.txt"))
+    );
+    assert_eq!(
+        find(&directory(Some(option_arg)), 7, 1, Variant::Input),
+        Ok(option.join("day07/input.txt"))
+    );
+
+    env::remove_var("AOC_INPUTS");
+}
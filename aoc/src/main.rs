@@ -0,0 +1,148 @@
+#[macro_use]
+extern crate log;
+
+mod args;
+mod bench;
+mod days;
+mod inputs;
+
+use std::{path::Path, process::ExitCode};
+
+use aoc_core::{Error, Part};
+use args::{BenchArgs, Command, Export, Input, Mode, Output, RunArgs, USAGE};
+
+/// The path of the file to read the puzzle input from.
+fn input_path(args: &RunArgs) -> std::result::Result<String, String> {
+    match &args.input {
+        Input::Path(path) => Ok(path.clone()),
+        Input::Find { variant, inputs } => {
+            let inputs = inputs::directory(inputs.as_deref());
+            let path = inputs::find(&inputs, args.day, days::problem(args.day), *variant)?;
+            debug!("reading {}", path.display());
+            Ok(path.to_string_lossy().into_owned())
+        }
+    }
+}
+
+/// The puzzle input at `path`, errors name the file.
+fn load(path: &str) -> std::result::Result<String, String> {
+    aoc_core::load(path).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn run(args: &RunArgs, path: &str) -> std::result::Result<(), String> {
+    let input = load(path)?;
+    let in_input = |e: Error| format!("{}: {}", path, e);
+
+    match &args.mode {
+        Mode::Answer => answer(args, &input).map_err(in_input)?,
+        Mode::Bench(bench_args) => bench(args, path, &input, bench_args)?,
+        Mode::Stats(output) => {
+            let stats = days::stats(args, &input).map_err(in_input)?;
+            match output {
+                Output::Text => print!("{}", stats),
+                Output::Json => println!("{}", stats.to_json()),
+            }
+        }
+        Mode::Trajectory(export) => {
+            let trajectory = days::trajectory(&input).map_err(in_input)?;
+            match export {
+                Export::Csv => print!("{}", trajectory.to_csv()),
+                Export::Json => print!("{}", trajectory.to_json()),
+                Export::Plot => print!("{}", trajectory.plot()),
+            }
+        }
+        Mode::Ranking => print!("{}", days::ranking(args, &input).map_err(in_input)?),
+        Mode::Analyze(analyze) => print!(
+            "{}",
+            days::analyze(args, &input, analyze.games, analyze.seed).map_err(in_input)?
+        ),
+        Mode::Adversary(board) => print!(
+            "{}",
+            days::adversary(args, &input, *board).map_err(in_input)?
+        ),
+    }
+
+    if let Some(path) = &args.render {
+        let part = args.parts[args.parts.len() - 1];
+        let image = days::render(args.day, &input, part).map_err(in_input)?;
+        aoc_grid::save(&image, Path::new(path)).map_err(|e| format!("{}: {}", path, e))?;
+    }
+
+    Ok(())
+}
+
+fn answer(args: &RunArgs, input: &str) -> aoc_core::Result<()> {
+    let answer = days::run(args, input)?;
+
+    if args.parts == Part::ALL {
+        print!("{}", answer);
+    } else {
+        for &part in &args.parts {
+            match answer.part(part) {
+                Some(value) => println!("part {}: {}", part, value),
+                None => println!("part {}: not solved yet", part),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn bench(
+    args: &RunArgs,
+    path: &str,
+    input: &str,
+    bench: &BenchArgs,
+) -> std::result::Result<(), String> {
+    let baseline_file = match &bench.baseline {
+        Some(baseline) => baseline.clone(),
+        None => format!("{}.bench", path),
+    };
+    let baseline_path = Path::new(&baseline_file);
+    let baseline =
+        bench::load_baseline(baseline_path).map_err(|e| format!("{}: {}", baseline_file, e))?;
+
+    let timings = days::bench(args.day, input, &args.parts, bench.iterations)
+        .map_err(|e| format!("{}: {}", path, e))?;
+
+    println!("Day {}, {} iterations", args.day, bench.iterations);
+    let regressions = bench::report(&timings, baseline.as_deref());
+
+    if baseline.is_none() || bench.save {
+        bench::save_baseline(baseline_path, &timings)
+            .map_err(|e| format!("{}: {}", baseline_file, e))?;
+        println!("saved baseline to {}", baseline_file);
+    }
+
+    match regressions.len() {
+        // a saved baseline accepts this run's timings
+        _ if bench.save => Ok(()),
+        0 => Ok(()),
+        n => Err(format!(
+            "{} phase(s) regressed against the baseline in {}",
+            n, baseline_file
+        )),
+    }
+}
+
+fn main() -> ExitCode {
+    env_logger::init();
+
+    debug!("starting up");
+    let result = match Command::from_args(std::env::args().skip(1)) {
+        Ok(Command::Run(args)) => input_path(&args).and_then(|path| run(&args, &path)),
+        Ok(Command::Gen(args)) => {
+            print!("{}", days::gen(args.day, args.seed, args.size));
+            Ok(())
+        }
+        Err(e) => Err(format!("{}\n{}", e, USAGE)),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Runs every day against the answers recorded in `answers.txt`.
+
+use std::{fs, path::Path, process::Command};
+
+enum Expected {
+    Answer(String),
+    NotSolved,
+    Unchecked,
+}
+
+impl Expected {
+    fn new(column: &str) -> Self {
+        match column {
+            "-" => Expected::NotSolved,
+            "?" => Expected::Unchecked,
+            answer => Expected::Answer(answer.to_string()),
+        }
+    }
+
+    fn output(&self, part: usize) -> Option<String> {
+        match self {
+            Expected::Answer(answer) => Some(format!("part {}: {}", part, answer)),
+            Expected::NotSolved => Some(format!("part {}: not solved yet", part)),
+            Expected::Unchecked => None,
+        }
+    }
+}
+
+struct Entry {
+    line: usize,
+    day: String,
+    input: String,
+    parts: [Expected; 2],
+}
+
+fn manifest(path: &Path) -> Vec<Entry> {
+    let manifest = fs::read_to_string(path).unwrap();
+
+    manifest
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(n, line)| {
+            let columns: Vec<&str> = line.split('|').map(str::trim).collect();
+            match columns[..] {
+                [day, input, part1, part2] => Entry {
+                    line: n + 1,
+                    day: day.to_string(),
+                    input: input.to_string(),
+                    parts: [Expected::new(part1), Expected::new(part2)],
+                },
+                _ => panic!("answers.txt:{}: expected 4 columns: {:?}", n + 1, line),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_answers() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let root = crate_dir.join("..");
+    let entries = manifest(&crate_dir.join("tests/answers.txt"));
+    assert!(!entries.is_empty(), "answers.txt has no entries");
+
+    let mut failures = Vec::new();
+    for entry in &entries {
+        for (part, expected) in (1..).zip(&entry.parts) {
+            let expected = match expected.output(part) {
+                Some(expected) => expected,
+                None => continue,
+            };
+
+            let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+                .args(["run", &entry.day, "--part", &part.to_string()])
+                .arg(root.join(&entry.input))
+                .output()
+                .unwrap();
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+
+            if !output.status.success() || stdout.trim_end() != expected {
+                failures.push(format!(
+                    "answers.txt:{}: day {} part {} on {:?}: expected {:?}, got {:?} {}",
+                    entry.line,
+                    entry.day,
+                    part,
+                    entry.input,
+                    expected,
+                    stdout.trim_end(),
+                    stderr.trim_end()
+                ));
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
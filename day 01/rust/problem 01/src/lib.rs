@@ -0,0 +1,574 @@
+use std::{cmp::Ordering, collections::VecDeque, fmt, str::FromStr};
+
+use aoc_core::{normalize, parse_token, Answer, Error, Generate, Result, Rng, Solution};
+use log::{debug, info, warn};
+
+const SHORT_WINDOW: usize = 1;
+const WINDOW: usize = 3;
+const HISTOGRAM_BUCKETS: u64 = 10;
+const HISTOGRAM_BAR: usize = 40;
+
+/// How the readings in a window are combined before windows are compared.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Aggregate {
+    Sum,
+    Mean,
+    Max,
+}
+
+impl FromStr for Aggregate {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "sum" => Ok(Aggregate::Sum),
+            "mean" => Ok(Aggregate::Mean),
+            "max" => Ok(Aggregate::Max),
+            other => Err(format!("expected sum, mean or max but got {:?}", other)),
+        }
+    }
+}
+
+impl fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Aggregate::Sum => write!(f, "sum"),
+            Aggregate::Mean => write!(f, "mean"),
+            Aggregate::Max => write!(f, "max"),
+        }
+    }
+}
+
+/// The last `size` readings, keeps just enough to aggregate them.
+struct Window {
+    size: usize,
+    readings: VecDeque<i64>,
+    /// Wide enough for any number of readings that fit in memory.
+    sum: i128,
+    /// Readings that can still become the maximum, from largest to smallest.
+    maxima: VecDeque<i64>,
+}
+
+impl Window {
+    fn new(size: usize) -> Self {
+        Window {
+            size,
+            readings: VecDeque::with_capacity(size + 1),
+            sum: 0,
+            maxima: VecDeque::with_capacity(size),
+        }
+    }
+
+    /// Slides over `reading`, returns the aggregate once the window is full. A mean is
+    /// returned as the sum it divides, every window has the same size to divide by.
+    fn push(&mut self, reading: i64, aggregate: Aggregate) -> Option<i128> {
+        self.readings.push_back(reading);
+        self.sum += i128::from(reading);
+        while self.maxima.back().is_some_and(|&max| max < reading) {
+            self.maxima.pop_back();
+        }
+        self.maxima.push_back(reading);
+
+        if self.readings.len() > self.size {
+            let dropped = self.readings.pop_front().unwrap();
+            self.sum -= i128::from(dropped);
+            if self.maxima.front() == Some(&dropped) {
+                self.maxima.pop_front();
+            }
+        }
+
+        (self.readings.len() == self.size).then(|| match aggregate {
+            Aggregate::Sum | Aggregate::Mean => self.sum,
+            Aggregate::Max => i128::from(self.maxima[0]),
+        })
+    }
+}
+
+/// Counts how often the aggregate of a window sliding over `readings` is larger than
+/// the one before, using memory for `window` readings only.
+pub fn count_increases(
+    readings: impl IntoIterator<Item = i64>,
+    window: usize,
+    aggregate: Aggregate,
+) -> usize {
+    let mut window = Window::new(window);
+    let aggregates = readings
+        .into_iter()
+        .filter_map(|reading| window.push(reading, aggregate));
+
+    steps(aggregates)
+        .filter(|(previous, current)| current > previous)
+        .count()
+}
+
+/// Every value after the first, paired with the one before it.
+fn steps<T: Copy>(values: impl IntoIterator<Item = T>) -> impl Iterator<Item = (T, T)> {
+    let mut values = values.into_iter();
+    let first = values.next();
+    values.scan(first, |last, value| Some((last.replace(value)?, value)))
+}
+
+/// Readings `start` to `end`, both included and counted from 0.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Run {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Run {
+    pub fn length(&self) -> usize {
+        self.end - self.start + 1
+    }
+}
+
+/// The readings from `from` to `to`, both included, and how many there are.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Bucket {
+    pub from: i64,
+    pub to: i64,
+    pub count: usize,
+}
+
+/// What the readings look like, to tell a broken depth log from a real one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    pub readings: usize,
+    pub increases: usize,
+    pub decreases: usize,
+    pub plateaus: usize,
+    /// The first of the longest runs of strictly increasing readings.
+    pub longest_run: Run,
+    pub largest_rise: Option<u64>,
+    pub largest_drop: Option<u64>,
+    /// Up to 10 buckets of equal width from the smallest to the largest reading.
+    pub histogram: Vec<Bucket>,
+}
+
+impl Stats {
+    /// Everything on one line, `null` for a rise or drop that did not happen.
+    pub fn to_json(&self) -> String {
+        let optional = |value: Option<u64>| value.map_or(String::from("null"), |v| v.to_string());
+        let buckets: Vec<String> = self
+            .histogram
+            .iter()
+            .map(|bucket| {
+                format!(
+                    r#"{{"from":{},"to":{},"count":{}}}"#,
+                    bucket.from, bucket.to, bucket.count
+                )
+            })
+            .collect();
+
+        format!(
+            r#"{{"readings":{},"increases":{},"decreases":{},"plateaus":{},"longest_run":{{"start":{},"end":{},"length":{}}},"largest_rise":{},"largest_drop":{},"histogram":[{}]}}"#,
+            self.readings,
+            self.increases,
+            self.decreases,
+            self.plateaus,
+            self.longest_run.start,
+            self.longest_run.end,
+            self.longest_run.length(),
+            optional(self.largest_rise),
+            optional(self.largest_drop),
+            buckets.join(",")
+        )
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let optional = |value: Option<u64>| value.map_or(String::from("none"), |v| v.to_string());
+
+        writeln!(f, "readings: {}", self.readings)?;
+        writeln!(f, "increases: {}", self.increases)?;
+        writeln!(f, "decreases: {}", self.decreases)?;
+        writeln!(f, "plateaus: {}", self.plateaus)?;
+        writeln!(
+            f,
+            "longest increasing run: {} readings, {} to {}",
+            self.longest_run.length(),
+            self.longest_run.start,
+            self.longest_run.end
+        )?;
+        writeln!(f, "largest rise: {}", optional(self.largest_rise))?;
+        writeln!(f, "largest drop: {}", optional(self.largest_drop))?;
+        writeln!(f, "histogram:")?;
+
+        let most = self.histogram.iter().map(|b| b.count).max().unwrap_or(0);
+        for bucket in &self.histogram {
+            let bar = (bucket.count * HISTOGRAM_BAR).div_ceil(most.max(1));
+            let line = format!(
+                "{:>8} to {:<8}{:>8} {}",
+                bucket.from,
+                bucket.to,
+                bucket.count,
+                "#".repeat(bar)
+            );
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Every whitespace separated token with its line and the number of that line.
+fn tokens(input: &str) -> impl Iterator<Item = (usize, &str, &str)> {
+    input.lines().enumerate().flat_map(|(n, line)| {
+        line.split_whitespace()
+            .map(move |token| (n + 1, line, token))
+    })
+}
+
+/// What lenient parsing accepts, the positive depths only.
+fn lenient_reading(token: &str) -> Option<i64> {
+    token.parse::<i64>().ok().filter(|&depth| depth > 0)
+}
+
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    /// Parsed again on every pass over the readings, rather than kept as numbers.
+    input: String,
+    readings: usize,
+    window: usize,
+    aggregate: Aggregate,
+    /// Skips what is not a positive depth instead of failing, `Some` number of skipped tokens.
+    skipped: Option<usize>,
+}
+
+impl Simulation {
+    /// Reads one depth per token, zero and negative depths included, fails on anything else.
+    pub fn new(input: String) -> Result<Self> {
+        let mut readings = 0;
+        for (n, line, token) in tokens(&input) {
+            parse_token::<i64>(line, token).map_err(|e| e.relative_to(n, 1))?;
+            readings += 1;
+        }
+        Self::with_readings(input, readings, None)
+    }
+
+    /// Like [`Simulation::new`], but skips (and warns about) every token that is not a
+    /// positive depth.
+    pub fn lenient(input: String) -> Result<Self> {
+        let mut readings = 0;
+        let mut skipped = 0;
+        for (n, _, token) in tokens(&input) {
+            match lenient_reading(token) {
+                Some(_) => readings += 1,
+                None => {
+                    warn!(
+                        "line {}: skipping {:?}, it is not a positive depth",
+                        n, token
+                    );
+                    skipped += 1;
+                }
+            }
+        }
+        Self::with_readings(input, readings, Some(skipped))
+    }
+
+    fn with_readings(input: String, readings: usize, skipped: Option<usize>) -> Result<Self> {
+        if readings == 0 {
+            return Err(Error::invalid("there are no depth readings"));
+        }
+        Ok(Simulation {
+            input,
+            readings,
+            window: WINDOW,
+            aggregate: Aggregate::Sum,
+            skipped,
+        })
+    }
+
+    /// Uses windows of `window` readings for part 2 instead of 3, has to be at least 1.
+    pub fn with_window(mut self, window: usize) -> Self {
+        assert!(window > 0, "windows need at least one reading");
+        self.window = window;
+        self
+    }
+
+    /// Compares windows by `aggregate` in part 2 instead of by their sum.
+    pub fn with_aggregate(mut self, aggregate: Aggregate) -> Self {
+        self.aggregate = aggregate;
+        self
+    }
+
+    /// The depths, every token is one unless parsing was lenient and skipped it.
+    fn readings(&self) -> impl Iterator<Item = i64> + '_ {
+        let lenient = self.skipped.is_some();
+        tokens(&self.input).filter_map(move |(_, _, token)| match lenient {
+            true => lenient_reading(token),
+            // new made sure every token is a reading
+            false => token.parse().ok(),
+        })
+    }
+
+    /// Goes over the readings twice, once for the changes and once for the histogram.
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats {
+            readings: self.readings,
+            increases: 0,
+            decreases: 0,
+            plateaus: 0,
+            longest_run: Run { start: 0, end: 0 },
+            largest_rise: None,
+            largest_drop: None,
+            histogram: Vec::new(),
+        };
+
+        // there is at least one reading, new made sure of that
+        let (mut min, mut max) = (i64::MAX, i64::MIN);
+        let mut run_start = 0;
+        let readings = self.readings().inspect(|&depth| {
+            min = min.min(depth);
+            max = max.max(depth);
+        });
+        for (index, (previous, depth)) in (1..).zip(steps(readings)) {
+            let change = depth.abs_diff(previous);
+            match depth.cmp(&previous) {
+                Ordering::Greater => {
+                    stats.increases += 1;
+                    stats.largest_rise = stats.largest_rise.max(Some(change));
+                }
+                Ordering::Less => {
+                    stats.decreases += 1;
+                    stats.largest_drop = stats.largest_drop.max(Some(change));
+                    run_start = index;
+                }
+                Ordering::Equal => {
+                    stats.plateaus += 1;
+                    run_start = index;
+                }
+            }
+            if index - run_start >= stats.longest_run.length() {
+                stats.longest_run = Run {
+                    start: run_start,
+                    end: index,
+                };
+            }
+        }
+
+        // distances from the smallest reading, any two readings are less than u64::MAX apart
+        let width = max.abs_diff(min) / HISTOGRAM_BUCKETS + 1;
+        let mut counts = vec![0; (max.abs_diff(min) / width + 1) as usize];
+        for depth in self.readings() {
+            counts[(depth.abs_diff(min) / width) as usize] += 1;
+        }
+        stats.histogram = (0..)
+            .zip(counts)
+            .map(|(bucket, count)| Bucket {
+                from: min.saturating_add_unsigned(bucket * width),
+                to: min
+                    .saturating_add_unsigned((bucket * width).saturating_add(width - 1))
+                    .min(max),
+                count,
+            })
+            .collect();
+
+        stats
+    }
+
+    pub fn run(&self) -> Answer {
+        info!("Counting increases");
+        let single = count_increases(self.readings(), SHORT_WINDOW, Aggregate::Sum);
+        debug!("{} single readings increase", single);
+        let windows = count_increases(self.readings(), self.window, self.aggregate);
+        info!("{} windows of {} increase", windows, self);
+
+        let answer = Answer::new(Some(single), Some(windows))
+            .with("readings", self.readings)
+            .with("window", self);
+        match self.skipped {
+            Some(skipped) => answer.with("skipped tokens", skipped),
+            None => answer,
+        }
+    }
+}
+
+impl Solution for Simulation {
+    fn parse(input: &str) -> Result<Self> {
+        Simulation::new(normalize(input))
+    }
+
+    fn part1(&self) -> Result<Option<usize>> {
+        Ok(Some(count_increases(
+            self.readings(),
+            SHORT_WINDOW,
+            Aggregate::Sum,
+        )))
+    }
+
+    fn part2(&self) -> Result<Option<usize>> {
+        Ok(Some(count_increases(
+            self.readings(),
+            self.window,
+            self.aggregate,
+        )))
+    }
+
+    fn answer(&self) -> Result<Answer> {
+        Ok(self.run())
+    }
+}
+
+impl fmt::Display for Simulation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} readings by {}", self.window, self.aggregate)
+    }
+}
+
+/// `size` is the number of depth readings, the sea floor goes down mostly.
+impl Generate for Simulation {
+    const DEFAULT_SIZE: usize = 2000;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut depth = rng.range(100..=200);
+        let mut output = String::new();
+        for _ in 0..size {
+            output.push_str(&format!("{}\n", depth));
+            depth = (depth + rng.range(0..=30)).saturating_sub(10).max(1);
+        }
+        output
+    }
+}
+
+#[test]
+fn test_generated_readings_match_collected_windows() -> Result<()> {
+    // the puzzle's way, with the windows collected and summed first
+    aoc_core::check_generated(1..=200, |simulation: &Simulation| {
+        let readings: Vec<i64> = simulation.readings().collect();
+        let sums: Vec<i64> = readings.windows(WINDOW).map(|w| w.iter().sum()).collect();
+        let increases = |values: &[i64]| values.windows(2).filter(|w| w[1] > w[0]).count();
+        [Some(increases(&readings)), Some(increases(&sums))]
+    })
+}
+
+#[test]
+fn test_count_increases_matches_collected_windows() {
+    let readings = [
+        199, 200, 208, 210, 200, 207, 240, 269, 260, 263, -5, 0, 0, 17, 3,
+    ];
+    let increases = |values: &[i64]| values.windows(2).filter(|w| w[1] > w[0]).count();
+
+    for window in 1..=5 {
+        let sums: Vec<i64> = readings.windows(window).map(|w| w.iter().sum()).collect();
+        let maxima: Vec<i64> = readings
+            .windows(window)
+            .map(|w| *w.iter().max().unwrap())
+            .collect();
+
+        for (aggregate, expected) in [
+            (Aggregate::Sum, increases(&sums)),
+            (Aggregate::Mean, increases(&sums)),
+            (Aggregate::Max, increases(&maxima)),
+        ] {
+            assert_eq!(
+                count_increases(readings, window, aggregate),
+                expected,
+                "{} of {}",
+                aggregate,
+                window
+            );
+        }
+    }
+}
+
+#[test]
+fn test_count_increases_at_the_extremes() {
+    // from here on f64 cannot tell every integer apart
+    let exact = 1 << f64::MANTISSA_DIGITS;
+
+    for aggregate in [Aggregate::Sum, Aggregate::Mean, Aggregate::Max] {
+        let readings = [exact + 1, exact, exact + 1];
+        assert_eq!(count_increases(readings, 1, aggregate), 1, "{}", aggregate);
+        let readings = [i64::MIN, i64::MAX, i64::MIN, i64::MAX];
+        assert_eq!(count_increases(readings, 1, aggregate), 2, "{}", aggregate);
+    }
+
+    // sums of windows beyond i64
+    for aggregate in [Aggregate::Sum, Aggregate::Mean] {
+        let readings = [exact + 1, exact, exact + 2];
+        assert_eq!(count_increases(readings, 2, aggregate), 1, "{}", aggregate);
+        let readings = [i64::MAX, i64::MAX, i64::MAX, 1];
+        assert_eq!(count_increases(readings, 3, aggregate), 0, "{}", aggregate);
+        let readings = [i64::MIN, i64::MIN, i64::MIN, i64::MIN + 1];
+        assert_eq!(count_increases(readings, 3, aggregate), 1, "{}", aggregate);
+    }
+    let readings = [i64::MAX, i64::MIN, i64::MIN, i64::MAX - 1];
+    assert_eq!(count_increases(readings, 3, Aggregate::Max), 0);
+    let readings = [i64::MIN, i64::MIN, i64::MIN, i64::MIN + 1];
+    assert_eq!(count_increases(readings, 3, Aggregate::Max), 1);
+}
+
+#[test]
+fn test_strict_and_lenient_readings() -> Result<()> {
+    let input = "199\n0 -3\n20O\n+7\n";
+
+    let error = Simulation::new(input.to_string()).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 3, column 1: could not parse \"20O\": invalid digit found in string"
+    );
+
+    let strict = Simulation::new(input.replace("20O", "200"))?;
+    assert_eq!(strict.readings().collect::<Vec<_>>(), [199, 0, -3, 200, 7]);
+
+    let lenient = Simulation::lenient(input.to_string())?;
+    assert_eq!(lenient.readings().collect::<Vec<_>>(), [199, 7]);
+    assert_eq!(lenient.run().get("skipped tokens"), Some("3"));
+
+    Ok(())
+}
+
+#[test]
+fn test_stats() -> Result<()> {
+    let sonar = Simulation::new(String::from(
+        "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n",
+    ))?;
+
+    let stats = sonar.stats();
+
+    assert_eq!(
+        (
+            stats.readings,
+            stats.increases,
+            stats.decreases,
+            stats.plateaus
+        ),
+        (10, 7, 2, 0)
+    );
+    assert_eq!(stats.longest_run, Run { start: 0, end: 3 });
+    assert_eq!(
+        (stats.largest_rise, stats.largest_drop),
+        (Some(33), Some(10))
+    );
+    assert_eq!(
+        stats.histogram.iter().map(|b| b.count).collect::<Vec<_>>(),
+        [3, 3, 0, 0, 0, 1, 0, 1, 2]
+    );
+    assert_eq!(
+        stats.histogram.last(),
+        Some(&Bucket {
+            from: 263,
+            to: 269,
+            count: 2
+        })
+    );
+    assert!(stats
+        .to_json()
+        .contains(r#""longest_run":{"start":0,"end":3,"length":4}"#));
+
+    // the whole range of i64, a rise and a drop of u64::MAX
+    let extremes = Simulation::new(format!("{}\n{}\n{}\n", i64::MIN, i64::MAX, i64::MIN))?;
+    let stats = extremes.stats();
+    assert_eq!(
+        (stats.largest_rise, stats.largest_drop),
+        (Some(u64::MAX), Some(u64::MAX))
+    );
+    assert_eq!(
+        stats.histogram.iter().map(|b| b.count).collect::<Vec<_>>(),
+        [2, 0, 0, 0, 0, 0, 0, 0, 0, 1]
+    );
+    assert_eq!(stats.histogram[0].from, i64::MIN);
+    assert_eq!(stats.histogram[9].to, i64::MAX);
+
+    Ok(())
+}
@@ -0,0 +1,248 @@
+//! The course language, the puzzle's `forward 5` lines plus `#` comments,
+//! `repeat N { ... }` blocks and `macro name { ... }` definitions.
+
+use std::{collections::HashMap, vec};
+
+use aoc_core::{column_of, Error, Result};
+
+use crate::{Command, Direction};
+
+/// Repeats and macros may not expand a course to more commands than this. With at most
+/// `u32::MAX` units each, positions, depths and aims stay far within an `i64`, only what
+/// they multiply to can overflow, which the submarines check.
+const MAX_COMMANDS: usize = 10_000_000;
+
+#[derive(Copy, Clone, Debug)]
+struct Token<'a> {
+    line: usize,
+    column: usize,
+    text: &'a str,
+}
+
+impl Token<'_> {
+    fn error(&self, message: impl Into<String>) -> Error {
+        Error::parse(self.line, self.column, message)
+    }
+
+    /// An error right behind this token, for something that is missing after it.
+    fn error_after(&self, message: impl Into<String>) -> Error {
+        Error::parse(self.line, self.column + self.text.chars().count(), message)
+    }
+}
+
+/// Splits `input` into words and braces, leaving out comments.
+fn tokens(input: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    for (n, line) in input.lines().enumerate() {
+        let code = line.split('#').next().unwrap_or_default();
+        for word in code.split_whitespace() {
+            // braces are tokens of their own, even right next to a word
+            let mut rest = word;
+            while !rest.is_empty() {
+                let end = match rest.find(['{', '}']) {
+                    Some(0) => 1,
+                    Some(brace) => brace,
+                    None => rest.len(),
+                };
+                tokens.push(Token {
+                    line: n + 1,
+                    column: column_of(line, &rest[..end]),
+                    text: &rest[..end],
+                });
+                rest = &rest[end..];
+            }
+        }
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    tokens: vec::IntoIter<Token<'a>>,
+    macros: HashMap<&'a str, Vec<Command>>,
+}
+
+/// Reads a course and expands its repeats and macros into the commands to execute.
+pub fn parse(input: &str) -> Result<Vec<Command>> {
+    let mut parser = Parser {
+        tokens: tokens(input).into_iter(),
+        macros: HashMap::new(),
+    };
+    parser.items(None)
+}
+
+impl<'a> Parser<'a> {
+    /// Reads items up to the `}` closing `open`, or up to the end of the course.
+    fn items(&mut self, open: Option<Token<'a>>) -> Result<Vec<Command>> {
+        let mut commands = Vec::new();
+        loop {
+            let token = match (self.tokens.next(), open) {
+                (Some(token), _) => token,
+                (None, Some(open)) => return Err(open.error("this block is never closed")),
+                (None, None) => return Ok(commands),
+            };
+
+            match token.text {
+                "}" if open.is_some() => return Ok(commands),
+                "{" | "}" => return Err(token.error(format!("unexpected {:?}", token.text))),
+                "repeat" => {
+                    let times = self.number(token)?;
+                    let body = self.block(token)?;
+                    expand(&mut commands, &body, times as usize, token)?;
+                }
+                "macro" => {
+                    let name = self.name(token)?;
+                    let body = self.block(name)?;
+                    self.macros.insert(name.text, body);
+                }
+                word => match self.macros.get(word) {
+                    Some(body) => expand(&mut commands, body, 1, token)?,
+                    None => {
+                        let direction: Direction = word.parse().map_err(|e| token.error(e))?;
+                        let units = match direction {
+                            Direction::Surface => 0,
+                            _ => self.number(token)?,
+                        };
+                        commands.push(Command {
+                            direction,
+                            units: units.into(),
+                        });
+                    }
+                },
+            }
+        }
+    }
+
+    /// A `{`, the items in it and the closing `}`.
+    fn block(&mut self, after: Token<'a>) -> Result<Vec<Command>> {
+        match self.tokens.next() {
+            Some(open) if open.text == "{" => self.items(Some(open)),
+            Some(token) => Err(token.error(format!("expected \"{{\" but got {:?}", token.text))),
+            None => Err(after.error_after("expected \"{\"")),
+        }
+    }
+
+    fn number(&mut self, after: Token<'a>) -> Result<u32> {
+        let token = self.tokens.next().ok_or_else(|| {
+            after.error_after(format!("expected a number after {:?}", after.text))
+        })?;
+        token
+            .text
+            .parse()
+            .map_err(|e| token.error(format!("could not parse {:?}: {}", token.text, e)))
+    }
+
+    /// The name of a new macro, which cannot be taken by a command or another macro.
+    fn name(&mut self, after: Token<'a>) -> Result<Token<'a>> {
+        let token = self
+            .tokens
+            .next()
+            .ok_or_else(|| after.error_after("expected the name of the macro"))?;
+        let reserved = matches!(token.text, "repeat" | "macro" | "{" | "}")
+            || token.text.parse::<Direction>().is_ok();
+        if reserved {
+            return Err(token.error(format!("{:?} cannot be the name of a macro", token.text)));
+        }
+        if self.macros.contains_key(token.text) {
+            return Err(token.error(format!("macro {:?} is already defined", token.text)));
+        }
+        Ok(token)
+    }
+}
+
+/// Appends `body` `times` times to `commands`, unless that makes the course too long.
+fn expand(commands: &mut Vec<Command>, body: &[Command], times: usize, token: Token) -> Result<()> {
+    let length = body
+        .len()
+        .checked_mul(times)
+        .and_then(|length| length.checked_add(commands.len()))
+        .filter(|&length| length <= MAX_COMMANDS);
+    if length.is_none() {
+        return Err(token.error(format!(
+            "the course expands to more than {} commands",
+            MAX_COMMANDS
+        )));
+    }
+
+    for _ in 0..times {
+        commands.extend_from_slice(body);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_course() -> Result<()> {
+    let course = "\
+# dive and come back up
+macro dive { down 2 forward 1 }
+repeat 2 {
+    dive
+    repeat 3{forward 1}  # braces may touch
+}
+back 4
+surface
+";
+    let commands: Vec<String> = parse(course)?.iter().map(Command::to_string).collect();
+    assert_eq!(
+        commands,
+        [
+            "down 2",
+            "forward 1",
+            "forward 1",
+            "forward 1",
+            "forward 1",
+            "down 2",
+            "forward 1",
+            "forward 1",
+            "forward 1",
+            "forward 1",
+            "back 4",
+            "surface"
+        ]
+    );
+
+    for (course, error) in [
+        (
+            "repeat 2 {\n  forward 1\n",
+            "line 1, column 10: this block is never closed",
+        ),
+        (
+            "forward",
+            "line 1, column 8: expected a number after \"forward\"",
+        ),
+        (
+            "macro up { forward 1 }",
+            "line 1, column 7: \"up\" cannot be the name of a macro",
+        ),
+        (
+            "repeat 100000 { repeat 1000 { forward 1 } }",
+            "line 1, column 1: the course expands to more than 10000000 commands",
+        ),
+    ] {
+        assert_eq!(parse(course).unwrap_err().to_string(), error);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_repeats_overflow_the_submarines() -> Result<()> {
+    use crate::Simulation;
+    use aoc_core::Solution;
+
+    let simulation = Simulation::parse("down 4000000000\nrepeat 3000 { forward 4000000000 }\n")?;
+    assert_eq!(
+        simulation.part1().unwrap_err().to_string(),
+        "invalid puzzle: the product of position 12000000000000 and depth 4000000000 overflows"
+    );
+    assert_eq!(
+        simulation.part2().unwrap_err().to_string(),
+        "invalid puzzle: the depth overflows on forward 4000000000"
+    );
+    assert!(simulation.trajectory().is_err());
+
+    // the largest course there is still moves the plain submarine within an i64
+    let simulation = Simulation::parse("repeat 10000000 { forward 4294967295 }\n")?;
+    assert_eq!(simulation.part1()?, Some(0));
+
+    Ok(())
+}
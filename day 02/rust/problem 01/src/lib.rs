@@ -0,0 +1,422 @@
+mod course;
+mod trajectory;
+
+pub use trajectory::{Step, Trajectory};
+
+use std::{fmt, str::FromStr};
+
+use aoc_core::{normalize, Answer, Error, Generate, Result, Rng, Solution};
+use log::{debug, info};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Direction {
+    Forward,
+    /// Backwards along the heading, undoes `forward`.
+    Back,
+    Down,
+    Up,
+    /// Straight up to the surface, takes no units.
+    Surface,
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "forward" => Ok(Direction::Forward),
+            "back" => Ok(Direction::Back),
+            "down" => Ok(Direction::Down),
+            "up" => Ok(Direction::Up),
+            "surface" => Ok(Direction::Surface),
+            other => Err(format!(
+                "expected forward, back, down, up, surface or a macro but got {:?}",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Direction::Forward => write!(f, "forward"),
+            Direction::Back => write!(f, "back"),
+            Direction::Down => write!(f, "down"),
+            Direction::Up => write!(f, "up"),
+            Direction::Surface => write!(f, "surface"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Command {
+    pub direction: Direction,
+    pub units: i64,
+}
+
+/// Reads a single command like `forward 5`, errors point at their column on line 1.
+impl FromStr for Command {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match course::parse(s)?[..] {
+            [command] => Ok(command),
+            _ => Err(Error::parse(1, 1, "expected a single command")),
+        }
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.direction {
+            Direction::Surface => write!(f, "{}", self.direction),
+            _ => write!(f, "{} {}", self.direction, self.units),
+        }
+    }
+}
+
+/// A way to read the commands, both parts steer the same commands differently.
+pub trait Submarine: Default + fmt::Display {
+    /// Fails if the command moves the submarine further than an `i64` reaches.
+    fn execute(&mut self, command: &Command) -> Result<()>;
+
+    fn position(&self) -> i64;
+
+    fn depth(&self) -> i64;
+
+    /// The answer, the horizontal position times the depth, fails if that overflows.
+    fn product(&self) -> Result<i64> {
+        self.position().checked_mul(self.depth()).ok_or_else(|| {
+            Error::invalid(format!(
+                "the product of position {} and depth {} overflows",
+                self.position(),
+                self.depth()
+            ))
+        })
+    }
+}
+
+/// `value` if computing it did not overflow, otherwise an error for `what` on `command`.
+fn checked(value: Option<i64>, what: &str, command: &Command) -> Result<i64> {
+    value.ok_or_else(|| Error::invalid(format!("the {} overflows on {}", what, command)))
+}
+
+/// Executes `commands` one after the other on a submarine `S` that starts at the surface.
+pub fn pilot<'a, S: Submarine>(commands: impl IntoIterator<Item = &'a Command>) -> Result<S> {
+    let mut submarine = S::default();
+    for command in commands {
+        submarine.execute(command)?;
+        debug!("{:<12} {}", command, submarine);
+    }
+    Ok(submarine)
+}
+
+/// Part 1, `down` and `up` change the depth directly.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Plain {
+    position: i64,
+    depth: i64,
+}
+
+impl Submarine for Plain {
+    fn execute(&mut self, command: &Command) -> Result<()> {
+        let units = command.units;
+        match command.direction {
+            Direction::Forward => {
+                self.position = checked(self.position.checked_add(units), "position", command)?
+            }
+            Direction::Back => {
+                self.position = checked(self.position.checked_sub(units), "position", command)?
+            }
+            Direction::Down => {
+                self.depth = checked(self.depth.checked_add(units), "depth", command)?
+            }
+            Direction::Up => self.depth = checked(self.depth.checked_sub(units), "depth", command)?,
+            Direction::Surface => self.depth = 0,
+        }
+        Ok(())
+    }
+
+    fn position(&self) -> i64 {
+        self.position
+    }
+
+    fn depth(&self) -> i64 {
+        self.depth
+    }
+}
+
+impl fmt::Display for Plain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "position {}, depth {}", self.position, self.depth)
+    }
+}
+
+/// Part 2, `down` and `up` change the aim, moving `forward` dives along it and `back`
+/// rises along it. Surfacing levels the submarine out.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Aimed {
+    position: i64,
+    depth: i64,
+    aim: i64,
+}
+
+impl Submarine for Aimed {
+    fn execute(&mut self, command: &Command) -> Result<()> {
+        let units = command.units;
+        let dive = || checked(self.aim.checked_mul(units), "depth", command);
+        match command.direction {
+            Direction::Forward => {
+                let depth = self.depth.checked_add(dive()?);
+                self.position = checked(self.position.checked_add(units), "position", command)?;
+                self.depth = checked(depth, "depth", command)?;
+            }
+            Direction::Back => {
+                let depth = self.depth.checked_sub(dive()?);
+                self.position = checked(self.position.checked_sub(units), "position", command)?;
+                self.depth = checked(depth, "depth", command)?;
+            }
+            Direction::Down => self.aim = checked(self.aim.checked_add(units), "aim", command)?,
+            Direction::Up => self.aim = checked(self.aim.checked_sub(units), "aim", command)?,
+            Direction::Surface => {
+                self.depth = 0;
+                self.aim = 0;
+            }
+        }
+        Ok(())
+    }
+
+    fn position(&self) -> i64 {
+        self.position
+    }
+
+    fn depth(&self) -> i64 {
+        self.depth
+    }
+}
+
+impl Aimed {
+    pub fn aim(&self) -> i64 {
+        self.aim
+    }
+}
+
+impl fmt::Display for Aimed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "position {}, depth {}, aim {}",
+            self.position, self.depth, self.aim
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    commands: Vec<Command>,
+}
+
+impl Simulation {
+    /// Reads a course, the puzzle's commands with comments, repeats and macros.
+    pub fn new(input: String) -> Result<Self> {
+        let commands = course::parse(&input)?;
+        if commands.is_empty() {
+            return Err(Error::invalid("there are no commands"));
+        }
+        Ok(Simulation { commands })
+    }
+
+    /// Where `S` ends up, an error if that is behind the start or above the surface.
+    fn product<S: Submarine>(&self) -> Result<usize> {
+        let submarine: S = pilot(&self.commands)?;
+        info!("The submarine ends up at {}", submarine);
+        let place = match (submarine.position() < 0, submarine.depth() < 0) {
+            (true, _) => "behind its starting point",
+            (false, true) => "above the surface",
+            (false, false) => {
+                let product = submarine.product()?;
+                return usize::try_from(product).map_err(|_| {
+                    Error::invalid(format!("the product {} does not fit an answer", product))
+                });
+            }
+        };
+        Err(Error::invalid(format!(
+            "the submarine ends up {} at {}",
+            place, submarine
+        )))
+    }
+
+    /// Where both submarines are after each command.
+    pub fn trajectory(&self) -> Result<Trajectory> {
+        Trajectory::new(&self.commands)
+    }
+
+    pub fn run(&self) -> Result<Answer> {
+        info!("Piloting {}", self);
+        Ok(Answer::new(
+            Some(self.product::<Plain>()?),
+            Some(self.product::<Aimed>()?),
+        )
+        .with("commands", self.commands.len()))
+    }
+}
+
+impl fmt::Display for Simulation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} commands", self.commands.len())
+    }
+}
+
+impl Solution for Simulation {
+    fn parse(input: &str) -> Result<Self> {
+        Simulation::new(normalize(input))
+    }
+
+    fn part1(&self) -> Result<Option<usize>> {
+        self.product::<Plain>().map(Some)
+    }
+
+    fn part2(&self) -> Result<Option<usize>> {
+        self.product::<Aimed>().map(Some)
+    }
+
+    fn answer(&self) -> Result<Answer> {
+        self.run()
+    }
+}
+
+/// `size` is the number of commands, the submarine never rises above the surface.
+impl Generate for Simulation {
+    const DEFAULT_SIZE: usize = 1000;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut depth = 0;
+        let mut output = String::new();
+        for _ in 0..size {
+            let units = rng.range(1..=9);
+            let direction = match rng.range(0..=2) {
+                0 if depth >= units => Direction::Up,
+                1 => Direction::Down,
+                _ => Direction::Forward,
+            };
+            match direction {
+                Direction::Up => depth -= units,
+                Direction::Down => depth += units,
+                _ => (),
+            }
+            output.push_str(&format!("{} {}\n", direction, units));
+        }
+        output
+    }
+}
+
+#[test]
+fn test_commands() -> Result<()> {
+    let simulation = Simulation::parse("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n")?;
+    let answer = simulation.run()?;
+    assert_eq!((answer.part1, answer.part2), (Some(150), Some(900)));
+
+    let error = Simulation::parse("forward 5\nsideways 3\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 2, column 1: expected forward, back, down, up, surface or a macro but got \"sideways\""
+    );
+    let error = Simulation::parse("forward 5\ndown -3\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 2, column 6: could not parse \"-3\": invalid digit found in string"
+    );
+
+    let simulation = Simulation::parse("forward 5\nback 9\ndown 3\n")?;
+    assert_eq!(
+        simulation.part1().unwrap_err().to_string(),
+        "invalid puzzle: the submarine ends up behind its starting point at position -4, depth 3"
+    );
+    let simulation = Simulation::parse("up 3\nforward 2\n")?;
+    assert_eq!(
+        simulation.part1().unwrap_err().to_string(),
+        "invalid puzzle: the submarine ends up above the surface at position 2, depth -3"
+    );
+
+    let simulation = Simulation::parse("forward 4000000000\ndown 4000000000\n")?;
+    assert_eq!(
+        simulation.part1().unwrap_err().to_string(),
+        "invalid puzzle: the product of position 4000000000 and depth 4000000000 overflows"
+    );
+    assert_eq!(simulation.part2()?, Some(0));
+    let simulation = Simulation::parse("down 4000000000\nforward 4000000000\n")?;
+    assert_eq!(
+        simulation.part2().unwrap_err().to_string(),
+        "invalid puzzle: the depth overflows on forward 4000000000"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_generated_courses_match_summed_commands() -> Result<()> {
+    // down and up move the plain submarine and aim the other one
+    aoc_core::check_generated(1..=200, |simulation: &Simulation| {
+        let (mut position, mut depth, mut aimed_depth) = (0, 0, 0);
+        for command in &simulation.commands {
+            match command.direction {
+                Direction::Forward => {
+                    position += command.units;
+                    aimed_depth += depth * command.units;
+                }
+                Direction::Down => depth += command.units,
+                Direction::Up => depth -= command.units,
+                other => panic!("unexpected {:?} in a generated course", other),
+            }
+        }
+        [position * depth, position * aimed_depth].map(|product| usize::try_from(product).ok())
+    })
+}
+
+#[test]
+fn test_trajectory() -> Result<()> {
+    let simulation = Simulation::parse("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n")?;
+    let trajectory = simulation.trajectory()?;
+
+    let csv = trajectory.to_csv();
+    assert_eq!(csv.lines().count(), 7);
+    assert_eq!(csv.lines().nth(3), Some("3,forward 8,13,5,40,5"));
+    assert_eq!(csv.lines().last(), Some("6,forward 2,15,10,60,10"));
+
+    let plot = trajectory.plot();
+    assert!(plot.starts_with("plain\n 0 |*                      *\n"));
+    assert!(plot.contains("\n\naimed\n 0 |*                      *\n"));
+    assert!(plot.ends_with(
+        "60 |                                                                       *\n"
+    ));
+
+    // the plot starts at the rearmost position, behind the starting point
+    let plot = Simulation::parse("back 5\ndown 3\nforward 2\n")?
+        .trajectory()?
+        .plot();
+    let plain: Vec<&str> = plot.lines().skip(1).take(12).collect();
+    assert_eq!(
+        plain[0],
+        "0 |*                                                                      *"
+    );
+    assert_eq!(plain[11], "3 |*                           *");
+
+    // depths that far apart only fit the plot's scale in i128
+    let plot = Simulation::parse("down 4000000000\nforward 2000000000\n")?
+        .trajectory()?
+        .plot();
+    assert!(plot.ends_with(
+        "8000000000000000000 |                                                                       *\n"
+    ));
+    // a course the submarines cannot follow has no trajectory to plot
+    let error = Simulation::parse("down 4000000000\nforward 4000000000\n")?
+        .trajectory()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid puzzle: the depth overflows on forward 4000000000"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use std::fmt::Write;
+
+use aoc_core::Result;
+use aoc_grid::Grid;
+
+use crate::{Aimed, Command, Plain, Submarine};
+
+const PLOT_WIDTH: usize = 72;
+const PLOT_HEIGHT: usize = 12;
+
+/// Where both submarines are after a command, they always share their position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    pub command: Command,
+    pub plain: Plain,
+    pub aimed: Aimed,
+}
+
+/// Every step of a course, to see where the plain and the aimed submarine part ways.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trajectory {
+    steps: Vec<Step>,
+}
+
+impl Trajectory {
+    /// Fails like [`crate::pilot`] does, if either submarine overflows.
+    pub fn new<'a>(commands: impl IntoIterator<Item = &'a Command>) -> Result<Self> {
+        let mut plain = Plain::default();
+        let mut aimed = Aimed::default();
+        let steps = commands
+            .into_iter()
+            .map(|command| {
+                plain.execute(command)?;
+                aimed.execute(command)?;
+                Ok(Step {
+                    command: *command,
+                    plain: plain.clone(),
+                    aimed: aimed.clone(),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Trajectory { steps })
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// One row per step, numbered from 1, with a header.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("step,command,position,depth,aimed_depth,aim\n");
+        for (n, step) in (1..).zip(&self.steps) {
+            writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                n,
+                step.command,
+                step.plain.position(),
+                step.plain.depth(),
+                step.aimed.depth(),
+                step.aimed.aim()
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    /// An array with one object per step and line, with the same fields as [`Trajectory::to_csv`].
+    pub fn to_json(&self) -> String {
+        let steps: Vec<String> = (1..)
+            .zip(&self.steps)
+            .map(|(n, step)| {
+                format!(
+                    r#"  {{"step":{},"command":"{}","position":{},"depth":{},"aimed_depth":{},"aim":{}}}"#,
+                    n,
+                    step.command,
+                    step.plain.position(),
+                    step.plain.depth(),
+                    step.aimed.depth(),
+                    step.aimed.aim()
+                )
+            })
+            .collect();
+
+        format!("[\n{}\n]\n", steps.join(",\n"))
+    }
+
+    /// Side views of the depth profile of both submarines, each scaled to its own depths.
+    pub fn plot(&self) -> String {
+        let plain: Vec<(i64, i64)> = self
+            .steps
+            .iter()
+            .map(|step| (step.plain.position(), step.plain.depth()))
+            .collect();
+        let aimed: Vec<(i64, i64)> = self
+            .steps
+            .iter()
+            .map(|step| (step.aimed.position(), step.aimed.depth()))
+            .collect();
+
+        format!("plain\n{}\naimed\n{}", side_view(&plain), side_view(&aimed))
+    }
+}
+
+/// Plots `(position, depth)` points from the surface down and from the rearmost position
+/// forward, with the depth of the first and last row on the left.
+fn side_view(points: &[(i64, i64)]) -> String {
+    let start = (0, 0);
+    let points = || std::iter::once(&start).chain(points);
+    let rear = points().map(|&(position, _)| position).min().unwrap();
+    let front = points().map(|&(position, _)| position).max().unwrap();
+    let top = points().map(|&(_, depth)| depth).min().unwrap();
+    let bottom = points().map(|&(_, depth)| depth).max().unwrap();
+
+    // in i128, the distance between two i64 is too far for an i64, let alone times the cells
+    let cell = |value: i64, low: i64, high: i64, cells: usize| {
+        let distance = i128::from(value) - i128::from(low);
+        let range = (i128::from(high) - i128::from(low)).max(1);
+        (distance * (cells as i128 - 1) / range) as usize
+    };
+    let mut plot = Grid::new(PLOT_HEIGHT, PLOT_WIDTH, ' ');
+    for &(position, depth) in points() {
+        let row = cell(depth, top, bottom, PLOT_HEIGHT);
+        let column = cell(position, rear, front, PLOT_WIDTH);
+        if let Some(point) = plot.get_mut((row, column)) {
+            *point = '*';
+        }
+    }
+
+    let label = top.to_string().len().max(bottom.to_string().len());
+    let mut output = String::new();
+    for row in 0..plot.rows() {
+        let depth = match row {
+            0 => top.to_string(),
+            row if row == plot.rows() - 1 => bottom.to_string(),
+            _ => String::new(),
+        };
+        let cells: String = plot.row(row).iter().collect();
+        writeln!(output, "{:>label$} |{}", depth, cells.trim_end()).unwrap();
+    }
+    output
+}
@@ -0,0 +1,148 @@
+use std::{fmt, str::FromStr};
+
+pub(crate) const WORD_BITS: usize = u64::BITS as usize;
+
+/// Counts of up to `2^PLANES - 1` rows are kept bit-sliced before they are flushed.
+const PLANES: usize = 8;
+
+/// The number of words a `width` bit number takes.
+pub(crate) fn words(width: usize) -> usize {
+    width.div_ceil(WORD_BITS)
+}
+
+/// Whether bit `position` of `words` is set, position 0 is the leftmost bit of the number
+/// and the lowest bit of the first word.
+pub(crate) fn bit(words: &[u64], position: usize) -> bool {
+    words[position / WORD_BITS] >> (position % WORD_BITS) & 1 == 1
+}
+
+/// A binary number of any width, e.g. the gamma rate of a report.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bits {
+    words: Vec<u64>,
+    width: usize,
+}
+
+impl Bits {
+    pub(crate) fn from_fn(width: usize, mut f: impl FnMut(usize) -> bool) -> Self {
+        let mut words = vec![0; words(width)];
+        for position in 0..width {
+            words[position / WORD_BITS] |= (f(position) as u64) << (position % WORD_BITS);
+        }
+        Bits { words, width }
+    }
+
+    pub(crate) fn from_words(words: &[u64], width: usize) -> Self {
+        Bits {
+            words: words.to_vec(),
+            width,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Whether bit `position` is set, position 0 is the leftmost bit.
+    pub fn get(&self, position: usize) -> bool {
+        assert!(position < self.width, "bit {} is out of bounds", position);
+        bit(&self.words, position)
+    }
+
+    /// Every bit flipped.
+    pub fn inverse(&self) -> Self {
+        Bits::from_fn(self.width, |position| !self.get(position))
+    }
+
+    /// The value of the number, `None` if it has more than 64 bits.
+    pub fn to_u64(&self) -> Option<u64> {
+        (self.width <= WORD_BITS).then(|| {
+            (0..self.width).fold(0, |value, position| value << 1 | self.get(position) as u64)
+        })
+    }
+}
+
+/// Reads a number in binary, e.g. a prefix to count.
+impl FromStr for Bits {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.as_bytes();
+        if let Some(digit) = s.chars().find(|&c| c != '0' && c != '1') {
+            return Err(format!("expected '0' or '1' but got {:?}", digit));
+        }
+        Ok(Bits::from_fn(digits.len(), |position| {
+            digits[position] == b'1'
+        }))
+    }
+}
+
+/// Writes the number in binary, with all of its bits.
+impl fmt::Display for Bits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for position in 0..self.width {
+            write!(f, "{}", self.get(position) as u8)?;
+        }
+        Ok(())
+    }
+}
+
+/// Counts the set bits of every column of `rows`, numbers of `words` words each.
+///
+/// The counts are bit-sliced: plane `j` of a word holds bit `j` of the counts of its
+/// 64 columns, so adding a row takes a few word operations instead of one per bit.
+pub(crate) fn column_counts<'a>(rows: impl Iterator<Item = &'a [u64]>, words: usize) -> Vec<usize> {
+    let mut counts = vec![0; words * WORD_BITS];
+    let mut planes = vec![[0u64; PLANES]; words];
+    let mut pending = 0;
+
+    for row in rows {
+        for (&word, planes) in row.iter().zip(&mut planes) {
+            let mut carry = word;
+            for plane in planes.iter_mut() {
+                if carry == 0 {
+                    break;
+                }
+                (*plane, carry) = (*plane ^ carry, *plane & carry);
+            }
+        }
+        pending += 1;
+        if pending == (1 << PLANES) - 1 {
+            flush(&mut planes, &mut counts);
+            pending = 0;
+        }
+    }
+    flush(&mut planes, &mut counts);
+
+    counts
+}
+
+/// Adds the bit-sliced counts in `planes` to `counts` and clears them.
+fn flush(planes: &mut [[u64; PLANES]], counts: &mut [usize]) {
+    for (word, planes) in planes.iter_mut().enumerate() {
+        for (weight, plane) in planes.iter_mut().enumerate() {
+            let mut bits = *plane;
+            while bits != 0 {
+                counts[word * WORD_BITS + bits.trailing_zeros() as usize] += 1 << weight;
+                bits &= bits - 1;
+            }
+            *plane = 0;
+        }
+    }
+}
+
+#[test]
+fn test_column_counts() {
+    let rows: Vec<[u64; 2]> = (0..1000u64).map(|n| [n, u64::MAX - n]).collect();
+
+    let counts = column_counts(rows.iter().map(|row| row.as_slice()), 2);
+
+    assert_eq!(counts.len(), 2 * WORD_BITS);
+    for (position, &count) in counts.iter().enumerate() {
+        let expected = rows
+            .iter()
+            .filter(|row| bit(row.as_slice(), position))
+            .count();
+        assert_eq!(count, expected, "column {}", position);
+    }
+}
@@ -0,0 +1,340 @@
+mod bits;
+mod trie;
+
+pub use bits::Bits;
+pub use trie::{Criteria, TieBreak, Trie};
+
+use std::fmt;
+
+use aoc_core::{normalize, Answer, Error, Generate, Result, Rng, Solution};
+use bits::{column_counts, WORD_BITS};
+use log::info;
+
+/// Whether `position` is set in at least as many of `count` numbers as it is not, ties go to 1.
+fn most_common(ones: usize, count: usize) -> bool {
+    ones * 2 >= count
+}
+
+/// Multiplies two ratings, `None` if they or their product do not fit in 64 bits.
+fn product(a: &Bits, b: &Bits) -> Option<u64> {
+    a.to_u64()?.checked_mul(b.to_u64()?)
+}
+
+/// The diagnostic report, binary numbers that all have the same, but any, number of bits.
+#[derive(Clone, Debug)]
+pub struct Report {
+    /// The numbers one after the other, `words` words each.
+    bits: Vec<u64>,
+    words: usize,
+    width: usize,
+}
+
+impl Report {
+    pub fn new(input: String) -> Result<Self> {
+        let mut bits = Vec::new();
+        let mut width = 0;
+        let mut words = 0;
+
+        for (n, line) in input.lines().enumerate() {
+            let line = line.trim_end().as_bytes();
+            if n == 0 {
+                width = line.len();
+                words = bits::words(width);
+                bits.reserve(input.len() / (width + 1) * words);
+            } else if line.len() != width {
+                return Err(Error::parse(
+                    n + 1,
+                    usize::min(line.len(), width) + 1,
+                    format!("expected {} bits but got {}", width, line.len()),
+                ));
+            }
+
+            for (chunk, digits) in line.chunks(WORD_BITS).enumerate() {
+                // '0' and '1' only differ in their lowest bit
+                if let Some(offset) = digits.iter().position(|&digit| digit & !1 != b'0') {
+                    let column = chunk * WORD_BITS + offset;
+                    let character = String::from_utf8_lossy(&line[column..]);
+                    return Err(Error::parse(
+                        n + 1,
+                        column + 1,
+                        format!(
+                            "expected '0' or '1' but got {:?}",
+                            character.chars().next().unwrap()
+                        ),
+                    ));
+                }
+                let word = digits.iter().enumerate().fold(0, |word, (offset, &digit)| {
+                    word | ((digit & 1) as u64) << offset
+                });
+                bits.push(word);
+            }
+        }
+
+        if bits.is_empty() {
+            return Err(Error::invalid("there are no numbers in the report"));
+        }
+        Ok(Report { bits, words, width })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len() / self.words
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub(crate) fn number(&self, index: usize) -> &[u64] {
+        &self.bits[index * self.words..(index + 1) * self.words]
+    }
+
+    /// Bit `position` of number `index`, without slicing out the number first.
+    fn bit(&self, index: usize, position: usize) -> bool {
+        self.bits[index * self.words + position / WORD_BITS] >> (position % WORD_BITS) & 1 == 1
+    }
+
+    /// How many numbers have each bit set, from the leftmost bit on.
+    pub fn column_counts(&self) -> Vec<usize> {
+        let mut counts = column_counts(self.bits.chunks(self.words), self.words);
+        counts.truncate(self.width);
+        counts
+    }
+
+    /// The most common bit of every position.
+    pub fn gamma(&self) -> Bits {
+        let counts = self.column_counts();
+        Bits::from_fn(self.width, |position| {
+            most_common(counts[position], self.len())
+        })
+    }
+
+    /// The least common bit of every position, the inverse of [`Report::gamma`].
+    pub fn epsilon(&self) -> Bits {
+        self.gamma().inverse()
+    }
+
+    /// Gamma times epsilon, `None` if that does not fit in 64 bits.
+    pub fn power_consumption(&self) -> Option<u64> {
+        let gamma = self.gamma();
+        product(&gamma, &gamma.inverse())
+    }
+
+    pub(crate) fn number_bits(&self, index: usize) -> Bits {
+        Bits::from_words(self.number(index), self.width)
+    }
+
+    /// The numbers by their prefixes, for ratings and prefix counts.
+    pub fn trie(&self) -> Trie<'_> {
+        Trie::new(self)
+    }
+
+    /// The oxygen generator rating, ties keep numbers with a 1.
+    fn oxygen_generator(trie: &Trie) -> Bits {
+        trie.rating(Criteria::MostCommon, TieBreak::One)
+            .expect("keeping a 1 on ties never fails")
+    }
+
+    /// The CO2 scrubber rating, ties keep numbers with a 0.
+    fn co2_scrubber(trie: &Trie) -> Bits {
+        trie.rating(Criteria::LeastCommon, TieBreak::Zero)
+            .expect("keeping a 0 on ties never fails")
+    }
+
+    pub fn oxygen_generator_rating(&self) -> Bits {
+        Self::oxygen_generator(&self.trie())
+    }
+
+    pub fn co2_scrubber_rating(&self) -> Bits {
+        Self::co2_scrubber(&self.trie())
+    }
+
+    /// Oxygen generator times CO2 scrubber rating, `None` if that does not fit in 64 bits.
+    pub fn life_support_rating(&self) -> Option<u64> {
+        let trie = self.trie();
+        product(&Self::oxygen_generator(&trie), &Self::co2_scrubber(&trie))
+    }
+
+    /// Explains why a product of two ratings has no answer.
+    fn too_wide(&self, what: &str) -> Error {
+        Error::invalid(format!(
+            "the {} of {} bit numbers does not fit in 64 bits",
+            what, self.width
+        ))
+    }
+
+    pub fn run(&self) -> Result<Answer> {
+        info!("Diagnosing {}", self);
+        let gamma = self.gamma();
+        let epsilon = gamma.inverse();
+        info!("gamma is {}, epsilon is {}", gamma, epsilon);
+        let trie = self.trie();
+        let (oxygen, co2) = (Self::oxygen_generator(&trie), Self::co2_scrubber(&trie));
+        info!("oxygen generator is {}, CO2 scrubber is {}", oxygen, co2);
+
+        let power = product(&gamma, &epsilon).ok_or_else(|| self.too_wide("power consumption"))?;
+        let life_support =
+            product(&oxygen, &co2).ok_or_else(|| self.too_wide("life support rating"))?;
+        Ok(
+            Answer::new(Some(power as usize), Some(life_support as usize))
+                .with("gamma", gamma.to_u64().unwrap())
+                .with("epsilon", epsilon.to_u64().unwrap())
+                .with("oxygen generator", oxygen.to_u64().unwrap())
+                .with("CO2 scrubber", co2.to_u64().unwrap()),
+        )
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} numbers of {} bits", self.len(), self.width)
+    }
+}
+
+impl Solution for Report {
+    fn parse(input: &str) -> Result<Self> {
+        Report::new(normalize(input))
+    }
+
+    fn part1(&self) -> Result<Option<usize>> {
+        match self.power_consumption() {
+            Some(power) => Ok(Some(power as usize)),
+            None => Err(self.too_wide("power consumption")),
+        }
+    }
+
+    fn part2(&self) -> Result<Option<usize>> {
+        match self.life_support_rating() {
+            Some(life_support) => Ok(Some(life_support as usize)),
+            None => Err(self.too_wide("life support rating")),
+        }
+    }
+
+    fn answer(&self) -> Result<Answer> {
+        self.run()
+    }
+}
+
+/// `size` is the number of 12 bit numbers in the report.
+impl Generate for Report {
+    const DEFAULT_SIZE: usize = 1000;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut output = String::new();
+        for _ in 0..size {
+            for _ in 0..12 {
+                output.push(if rng.chance(0.5) { '1' } else { '0' });
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+#[test]
+fn test_gamma_calculcation() -> Result<()> {
+    for (input, expected) in [("00100", 4), ("00001", 1), ("10110", 22)] {
+        assert_eq!(
+            Report::parse(input)?.gamma().to_u64(),
+            Some(expected),
+            "{}",
+            input
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_life_support() -> Result<()> {
+    let report = Report::parse(
+        "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n",
+    )?;
+
+    assert_eq!(
+        (report.gamma().to_u64(), report.epsilon().to_u64()),
+        (Some(22), Some(9))
+    );
+    assert_eq!(
+        (
+            report.oxygen_generator_rating().to_u64(),
+            report.co2_scrubber_rating().to_u64()
+        ),
+        (Some(23), Some(10))
+    );
+
+    let error = Report::parse("00100\n1120\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 2, column 5: expected 5 bits but got 4"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_generated_ratings_match_filtered_numbers() -> Result<()> {
+    // the puzzle's way, on the numbers of the report
+    aoc_core::check_generated(1..=100, |report: &Report| {
+        let width = report.width();
+        let numbers: Vec<u64> = (0..report.len())
+            .map(|index| report.number_bits(index).to_u64().unwrap())
+            .collect();
+        let bit = |number: u64, position: usize| (number >> (width - 1 - position)) & 1 == 1;
+        let most_common = |numbers: &[u64], position: usize| {
+            numbers.iter().filter(|&&n| bit(n, position)).count() * 2 >= numbers.len()
+        };
+        let keep = |most: bool| {
+            let mut numbers = numbers.clone();
+            for position in 0..width {
+                if numbers.len() == 1 {
+                    break;
+                }
+                let wanted = most_common(&numbers, position) == most;
+                // a bit no number has leaves the numbers as they are
+                if numbers.iter().any(|&n| bit(n, position) == wanted) {
+                    numbers.retain(|&n| bit(n, position) == wanted);
+                }
+            }
+            numbers[0]
+        };
+
+        let gamma = (0..width).fold(0, |gamma, position| {
+            gamma << 1 | most_common(&numbers, position) as u64
+        });
+        let epsilon = !gamma & ((1 << width) - 1);
+        [gamma * epsilon, keep(true) * keep(false)].map(|rating| usize::try_from(rating).ok())
+    })
+}
+
+#[test]
+fn test_wide_report() -> Result<()> {
+    // 130 bits, the rows only differ in their last two columns
+    let prefix = "10".repeat(64);
+    let input = format!("{0}11\n{0}10\n{0}00\n", prefix);
+    let report = Report::parse(&input)?;
+
+    assert_eq!(report.width(), 130);
+    assert_eq!(report.gamma().to_string(), format!("{}10", prefix));
+    assert_eq!(
+        report.epsilon().to_string(),
+        format!("{}01", "01".repeat(64))
+    );
+    assert_eq!(
+        report.oxygen_generator_rating().to_string(),
+        format!("{}11", prefix)
+    );
+    assert_eq!(
+        report.co2_scrubber_rating().to_string(),
+        format!("{}00", prefix)
+    );
+    assert_eq!(
+        report.part1().unwrap_err().to_string(),
+        "invalid puzzle: the power consumption of 130 bit numbers does not fit in 64 bits"
+    );
+
+    Ok(())
+}
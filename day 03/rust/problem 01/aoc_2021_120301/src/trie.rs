@@ -0,0 +1,267 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use aoc_core::{Error, Result};
+use log::debug;
+
+use crate::{bits::bit, Bits, Report};
+
+/// Which bit a rating keeps, the one most or least of the remaining numbers have.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Criteria {
+    MostCommon,
+    LeastCommon,
+}
+
+/// What a rating keeps when a bit is set in exactly half of the remaining numbers.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TieBreak {
+    One,
+    Zero,
+    /// Ties are an error, for reports that are supposed to have none.
+    Fail,
+}
+
+impl FromStr for TieBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "1" | "one" => Ok(TieBreak::One),
+            "0" | "zero" => Ok(TieBreak::Zero),
+            "fail" => Ok(TieBreak::Fail),
+            other => Err(format!("expected one, zero or fail but got {:?}", other)),
+        }
+    }
+}
+
+impl fmt::Display for TieBreak {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TieBreak::One => write!(f, "one"),
+            TieBreak::Zero => write!(f, "zero"),
+            TieBreak::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+/// Marks a missing child, the root is nobody's child.
+const NONE: usize = 0;
+
+/// Reports up to this wide are tallied by value first, so that the trie only has to
+/// insert every distinct number once.
+const DENSE_WIDTH: usize = 16;
+
+#[derive(Clone, Debug)]
+struct Node {
+    /// How many numbers start with the prefix leading here.
+    count: usize,
+    /// One of these numbers, in a leaf all of them are equal to it.
+    entry: usize,
+    children: [usize; 2],
+}
+
+impl Node {
+    fn leaf(entry: usize, count: usize) -> Self {
+        Node {
+            count,
+            entry,
+            children: [NONE; 2],
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children == [NONE; 2]
+    }
+}
+
+/// The numbers of a report by their prefixes, bit 0 first.
+///
+/// A prefix that only one number has, or only equal numbers, ends in a leaf holding them,
+/// so numbers that differ early take a few nodes, no matter how wide they are.
+#[derive(Clone, Debug)]
+pub struct Trie<'a> {
+    report: &'a Report,
+    nodes: Vec<Node>,
+}
+
+impl<'a> Trie<'a> {
+    pub fn new(report: &'a Report) -> Self {
+        let mut trie = Trie {
+            report,
+            nodes: Vec::new(),
+        };
+        if report.width() <= DENSE_WIDTH {
+            // (count, first index) of every value
+            let mut tally = vec![(0, 0); 1 << report.width()];
+            for index in (0..report.len()).rev() {
+                let value = &mut tally[report.number(index)[0] as usize];
+                *value = (value.0 + 1, index);
+            }
+            for (count, index) in tally {
+                if count > 0 {
+                    trie.insert(index, count);
+                }
+            }
+        } else {
+            for index in 0..report.len() {
+                trie.insert(index, 1);
+            }
+        }
+        debug!(
+            "{} numbers make {} trie nodes",
+            report.len(),
+            trie.nodes.len()
+        );
+        trie
+    }
+
+    /// Adds `count` numbers equal to number `index`.
+    fn insert(&mut self, index: usize, count: usize) {
+        if self.nodes.is_empty() {
+            self.nodes.push(Node::leaf(index, count));
+            return;
+        }
+
+        let number = self.report.number(index);
+        let mut node = 0;
+        for position in 0..self.report.width() {
+            let current = &self.nodes[node];
+            if current.is_leaf() {
+                let entry = current.entry;
+                if self.report.number(entry) == number {
+                    self.nodes[node].count += count;
+                    return;
+                }
+                // move the leaf's numbers one level down, they split off where they differ
+                let child = self.push(Node::leaf(entry, current.count));
+                self.nodes[node].children[self.report.bit(entry, position) as usize] = child;
+            }
+            self.nodes[node].count += count;
+
+            let bit = bit(number, position) as usize;
+            match self.nodes[node].children[bit] {
+                NONE => {
+                    let child = self.push(Node::leaf(index, count));
+                    self.nodes[node].children[bit] = child;
+                    return;
+                }
+                child => node = child,
+            }
+        }
+        // all bits matched, the leaf below the last one holds equal numbers
+        self.nodes[node].count += count;
+    }
+
+    fn push(&mut self, node: Node) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    fn count(&self, node: usize) -> usize {
+        match node {
+            NONE => 0,
+            node => self.nodes[node].count,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.first().map_or(0, |root| root.count)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// How many numbers of the report start with `prefix`.
+    pub fn count_prefix(&self, prefix: &Bits) -> usize {
+        if prefix.width() > self.report.width() || self.is_empty() {
+            return 0;
+        }
+
+        let mut node = 0;
+        for position in 0..prefix.width() {
+            let current = &self.nodes[node];
+            if current.is_leaf() {
+                // the rest of the prefix has to match the leaf's numbers
+                let matches = (position..prefix.width())
+                    .all(|p| self.report.bit(current.entry, p) == prefix.get(p));
+                return if matches { current.count } else { 0 };
+            }
+            match current.children[prefix.get(position) as usize] {
+                NONE => return 0,
+                child => node = child,
+            }
+        }
+        self.nodes[node].count
+    }
+
+    /// Follows the bit picked by `criteria` and `tie` from the first bit on, until a single
+    /// number is left, one step per bit.
+    pub fn rating(&self, criteria: Criteria, tie: TieBreak) -> Result<Bits> {
+        if self.is_empty() {
+            return Err(Error::invalid("there are no numbers in the report"));
+        }
+
+        let mut node = 0;
+        let mut position = 0;
+        while !self.nodes[node].is_leaf() {
+            let [zeros, ones] = self.nodes[node].children;
+            let bit = match (self.count(zeros), self.count(ones)) {
+                // when all numbers share this bit, the least common one filters out nothing
+                (0, _) => true,
+                (_, 0) => false,
+                (zeros, ones) => match (ones.cmp(&zeros), criteria) {
+                    (Ordering::Greater, Criteria::MostCommon) => true,
+                    (Ordering::Less, Criteria::LeastCommon) => true,
+                    (Ordering::Equal, _) => match tie {
+                        TieBreak::One => true,
+                        TieBreak::Zero => false,
+                        TieBreak::Fail => {
+                            return Err(Error::invalid(format!(
+                                "bit {} is set in {} of {} numbers",
+                                position,
+                                ones,
+                                ones + zeros
+                            )))
+                        }
+                    },
+                    _ => false,
+                },
+            };
+            node = self.nodes[node].children[bit as usize];
+            position += 1;
+        }
+        // a leaf only holds equal numbers, they all give the same rating
+        Ok(self.report.number_bits(self.nodes[node].entry))
+    }
+}
+
+#[test]
+fn test_trie() -> Result<()> {
+    use aoc_core::Solution;
+
+    let report = Report::parse(
+        "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n",
+    )?;
+    let trie = Trie::new(&report);
+
+    let count = |prefix: &str| trie.count_prefix(&prefix.parse().unwrap());
+    assert_eq!(count(""), 12);
+    assert_eq!((count("0"), count("1")), (5, 7));
+    assert_eq!((count("1011"), count("10110"), count("10100")), (2, 1, 0));
+    assert_eq!(count("101101"), 0);
+
+    let rating = |criteria, tie| trie.rating(criteria, tie).map(|bits| bits.to_string());
+    assert_eq!(rating(Criteria::MostCommon, TieBreak::One)?, "10111");
+    assert_eq!(rating(Criteria::LeastCommon, TieBreak::Zero)?, "01010");
+    // after 1 and 0, 10110 and 10111 tie on the last bit
+    assert_eq!(rating(Criteria::MostCommon, TieBreak::Zero)?, "10110");
+    assert_eq!(
+        rating(Criteria::MostCommon, TieBreak::Fail)
+            .unwrap_err()
+            .to_string(),
+        "invalid puzzle: bit 4 is set in 1 of 2 numbers"
+    );
+
+    Ok(())
+}
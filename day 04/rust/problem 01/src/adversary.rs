@@ -0,0 +1,314 @@
+//! Draw orders picked against the other boards, to make one board win first or last.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use aoc_core::{Error, Result};
+use log::debug;
+
+use crate::Bingo;
+
+/// The distinct numbers of every line of a board that can be completed with `available`.
+fn winnable_lines(bingo: &Bingo, board: usize, available: &HashSet<usize>) -> Vec<Vec<usize>> {
+    let numbers = &bingo.boards[board].numbers;
+    bingo
+        .lines
+        .iter()
+        .map(|line| {
+            let mut line: Vec<usize> = line
+                .iter()
+                .map(|&(row, column)| numbers[row][column].number)
+                .collect();
+            line.sort_unstable();
+            line.dedup();
+            line
+        })
+        .filter(|line| line.iter().all(|number| available.contains(number)))
+        .collect()
+}
+
+/// Looks for numbers to hold back until the target board wins, so that every other board
+/// that can win has won before.
+///
+/// The held back numbers have to hit every line of the target board, and leave at least
+/// one line of every other board untouched. Holding back fewer numbers only helps the
+/// other boards, so the search only adds numbers to hit a line the target could still
+/// complete, and gives up on a branch as soon as it hits every line of another board.
+struct Adversary {
+    /// The lines of the target board.
+    target: Vec<Vec<usize>>,
+    /// The lines of the other boards that can win, `(board, line)` for every number on them.
+    index: HashMap<usize, Vec<(usize, usize)>>,
+    /// How many held back numbers are on each line of each other board.
+    hits: Vec<Vec<usize>>,
+    /// How many lines of each other board are not hit.
+    open: Vec<usize>,
+    held: Vec<usize>,
+}
+
+impl Adversary {
+    fn new(target: Vec<Vec<usize>>, others: Vec<Vec<Vec<usize>>>) -> Self {
+        let mut index: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+        for (board, lines) in others.iter().enumerate() {
+            for (line, numbers) in lines.iter().enumerate() {
+                for &number in numbers {
+                    index.entry(number).or_default().push((board, line));
+                }
+            }
+        }
+
+        Adversary {
+            target,
+            index,
+            hits: others.iter().map(|lines| vec![0; lines.len()]).collect(),
+            open: others.iter().map(Vec::len).collect(),
+            held: Vec::new(),
+        }
+    }
+
+    /// Holds back `number`, `false` if that keeps another board from winning.
+    fn hold(&mut self, number: usize) -> bool {
+        self.held.push(number);
+        let mut possible = true;
+        for &(board, line) in self.index.get(&number).into_iter().flatten() {
+            self.hits[board][line] += 1;
+            if self.hits[board][line] == 1 {
+                self.open[board] -= 1;
+                possible &= self.open[board] > 0;
+            }
+        }
+        possible
+    }
+
+    fn release(&mut self) {
+        let number = self.held.pop().unwrap();
+        for &(board, line) in self.index.get(&number).into_iter().flatten() {
+            self.hits[board][line] -= 1;
+            if self.hits[board][line] == 0 {
+                self.open[board] += 1;
+            }
+        }
+    }
+
+    /// How many lines of other boards holding back `number` would hit.
+    fn cost(&self, number: usize) -> usize {
+        self.index.get(&number).map_or(0, Vec::len)
+    }
+
+    /// Whether the numbers held so far can be extended to hit every line of the target.
+    fn search(&mut self, steps: &mut usize) -> bool {
+        *steps += 1;
+        let held = &self.held;
+        let line = self
+            .target
+            .iter()
+            .filter(|line| !line.iter().any(|number| held.contains(number)))
+            .min_by_key(|line| line.len());
+        let mut candidates = match line {
+            Some(line) => line.clone(),
+            None => return true,
+        };
+        // the numbers hitting the fewest lines of the other boards are the most promising
+        candidates.sort_by_key(|&number| self.cost(number));
+
+        for number in candidates {
+            if self.hold(number) && self.search(steps) {
+                return true;
+            }
+            self.release();
+        }
+        false
+    }
+}
+
+/// Draw orders making one board win first or last, as far as there are any.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Witnesses {
+    pub board: usize,
+    /// See [`Bingo::fastest_win`].
+    pub first: Option<Vec<usize>>,
+    /// See [`Bingo::last_win_order`].
+    pub last: Option<Vec<usize>>,
+}
+
+impl fmt::Display for Witnesses {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let list = |numbers: &[usize]| {
+            let numbers: Vec<String> = numbers.iter().map(usize::to_string).collect();
+            numbers.join(",")
+        };
+        match &self.first {
+            Some(draws) => writeln!(
+                f,
+                "board {} wins first after {} draws: {}",
+                self.board,
+                draws.len(),
+                list(draws)
+            )?,
+            None => writeln!(
+                f,
+                "board {} cannot win before every other board",
+                self.board
+            )?,
+        }
+        match &self.last {
+            Some(order) => writeln!(f, "board {} wins last with: {}", self.board, list(order)),
+            None => writeln!(f, "board {} cannot win after every other board", self.board),
+        }
+    }
+}
+
+impl Bingo {
+    /// How board `id` can win first and last.
+    pub fn witnesses(&self, id: usize) -> Result<Witnesses> {
+        Ok(Witnesses {
+            board: id,
+            first: self.fastest_win(id)?,
+            last: self.last_win_order(id)?,
+        })
+    }
+
+    /// The index of the board with `id`.
+    fn board_index(&self, id: usize) -> Result<usize> {
+        match id {
+            1.. if id <= self.boards.len() => Ok(id - 1),
+            _ => Err(Error::invalid(format!(
+                "there is no board {}, the boards are numbered from 1 to {}",
+                id,
+                self.boards.len()
+            ))),
+        }
+    }
+
+    /// The shortest draws of the drawn numbers that make board `id` win before every other
+    /// board, `None` if no draws do.
+    ///
+    /// A board wins first with as few draws as possible by drawing just the numbers of one of
+    /// its lines, so these are the numbers of its shortest line that completes no other board.
+    pub fn fastest_win(&self, id: usize) -> Result<Option<Vec<usize>>> {
+        let target = self.board_index(id)?;
+        let available: HashSet<usize> = self.numbers.iter().copied().collect();
+
+        let mut lines = winnable_lines(self, target, &available);
+        lines.sort_by_key(Vec::len);
+        let others: Vec<Vec<Vec<usize>>> = (0..self.boards.len())
+            .filter(|&board| board != target)
+            .map(|board| winnable_lines(self, board, &available))
+            .collect();
+
+        Ok(lines.into_iter().find(|line| {
+            let drawn: HashSet<usize> = line.iter().copied().collect();
+            others
+                .iter()
+                .flatten()
+                .all(|other| !other.iter().all(|number| drawn.contains(number)))
+        }))
+    }
+
+    /// An order of the drawn numbers in which board `id` wins after every other board that
+    /// wins at all, `None` if there is no such order.
+    pub fn last_win_order(&self, id: usize) -> Result<Option<Vec<usize>>> {
+        let target = self.board_index(id)?;
+        let available: HashSet<usize> = self.numbers.iter().copied().collect();
+
+        let lines = winnable_lines(self, target, &available);
+        if lines.is_empty() {
+            return Ok(None);
+        }
+        let others = (0..self.boards.len())
+            .filter(|&board| board != target)
+            .map(|board| winnable_lines(self, board, &available))
+            .filter(|lines| !lines.is_empty())
+            .collect();
+
+        let mut adversary = Adversary::new(lines, others);
+        let mut steps = 0;
+        let found = adversary.search(&mut steps);
+        debug!("the search for board {} took {} steps", id, steps);
+        if !found {
+            return Ok(None);
+        }
+
+        // drop the numbers the target board does not need to be held back, then each of the
+        // others is the only one held back on some line of it, and completes that line
+        let target = &adversary.target;
+        let hits_all = |held: &[usize]| {
+            target
+                .iter()
+                .all(|line| line.iter().any(|n| held.contains(n)))
+        };
+        let mut held = adversary.held.clone();
+        let mut position = 0;
+        while position < held.len() {
+            let number = held.remove(position);
+            if !hits_all(&held) {
+                held.insert(position, number);
+                position += 1;
+            }
+        }
+        let last = held[0];
+
+        let (mut order, mut rest): (Vec<usize>, Vec<usize>) = self
+            .numbers
+            .iter()
+            .partition(|number| !held.contains(number));
+        let position = rest.iter().position(|&number| number == last).unwrap();
+        order.push(rest.remove(position));
+        order.extend(rest);
+
+        Ok(Some(order))
+    }
+}
+
+#[test]
+fn test_adversary() -> Result<()> {
+    use aoc_core::Solution;
+
+    let bingo = Bingo::parse(include_str!("../example.txt"))?;
+    let replay = |numbers: Vec<usize>| Bingo {
+        numbers,
+        ..bingo.clone()
+    };
+
+    for id in 1..=3 {
+        let draws = bingo.fastest_win(id)?.expect("every board can win first");
+        assert_eq!(draws.len(), 5);
+        let win = replay(draws.clone()).play(crate::Strategy::First)?;
+        assert_eq!((win.board, win.draw), (id, 4), "{:?}", draws);
+
+        let order = bingo.last_win_order(id)?.expect("every board can win last");
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        let mut numbers = bingo.numbers.clone();
+        numbers.sort_unstable();
+        assert_eq!(sorted, numbers, "the order draws every number once");
+        let wins: Vec<_> = replay(order).wins().collect();
+        assert_eq!(wins.len(), 3);
+        assert_eq!(wins[2].board, id);
+        assert!(wins[1].draw < wins[2].draw);
+    }
+
+    // the first two boards always win together, so neither wins before or after the other
+    let bingo = Bingo::parse("1,2,3,4\n\n1 2\n\n2 1\n\n3 4\n")?
+        .with_patterns(vec![crate::WinPattern::Rows])?;
+    assert_eq!(bingo.fastest_win(1)?, None);
+    assert_eq!(bingo.last_win_order(1)?, None);
+    assert_eq!(bingo.fastest_win(3)?, Some(vec![3, 4]));
+    assert_eq!(bingo.last_win_order(3)?, Some(vec![1, 2, 4, 3]));
+    assert_eq!(
+        bingo.fastest_win(4).unwrap_err().to_string(),
+        "invalid puzzle: there is no board 4, the boards are numbered from 1 to 3"
+    );
+    assert_eq!(
+        bingo.witnesses(1)?.to_string(),
+        "board 1 cannot win before every other board\nboard 1 cannot win after every other board\n"
+    );
+    assert_eq!(
+        bingo.witnesses(3)?.to_string(),
+        "board 3 wins first after 2 draws: 3,4\nboard 3 wins last with: 1,2,4,3\n"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+//! How likely each board is to win first or last when the numbers are drawn in any order.
+
+use std::fmt;
+
+use aoc_core::{Error, Result, Rng};
+
+use crate::{Bingo, BingoGame};
+
+/// The z-score of a 95% confidence interval.
+const Z: f64 = 1.96;
+
+/// A mean over simulated games and the margin of its 95% confidence interval.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub margin: f64,
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision().unwrap_or(3);
+        write!(
+            f,
+            "{:.*} ± {:.*}",
+            precision, self.mean, precision, self.margin
+        )
+    }
+}
+
+/// Sums of samples, to estimate their mean.
+#[derive(Copy, Clone, Debug, Default)]
+struct Tally {
+    count: usize,
+    sum: f64,
+    squares: f64,
+}
+
+impl Tally {
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.squares += value * value;
+    }
+
+    /// The mean with the margin of the normal approximation, `None` without samples.
+    fn estimate(&self) -> Option<Estimate> {
+        if self.count == 0 {
+            return None;
+        }
+        let n = self.count as f64;
+        let mean = self.sum / n;
+        let variance = match self.count {
+            1 => 0.0,
+            _ => ((self.squares - n * mean * mean) / (n - 1.0)).max(0.0),
+        };
+
+        Some(Estimate {
+            mean,
+            margin: Z * (variance / n).sqrt(),
+        })
+    }
+}
+
+/// How one board fares over all simulated games.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Odds {
+    /// The id of the board.
+    pub board: usize,
+    /// The probability that it is the first board to win.
+    pub first: Estimate,
+    /// The probability that it is the last board to win.
+    pub last: Estimate,
+    /// How many numbers are drawn until it wins, over the games it wins, `None` if it never does.
+    pub draws: Option<Estimate>,
+}
+
+/// The odds of every board over games with the numbers drawn in random orders.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Analysis {
+    games: usize,
+    seed: u64,
+    odds: Vec<Odds>,
+}
+
+impl Analysis {
+    pub fn odds(&self) -> &[Odds] {
+        &self.odds
+    }
+}
+
+impl Bingo {
+    /// Plays `games` games, each drawing the numbers in another order picked from `seed`.
+    ///
+    /// Boards finishing on the same number rank by their ids, as they do in a single game.
+    pub fn analyze(&self, games: usize, seed: u64) -> Result<Analysis> {
+        if games == 0 {
+            return Err(Error::invalid("an analysis needs at least one game"));
+        }
+
+        let mut first = vec![Tally::default(); self.boards.len()];
+        let mut last = vec![Tally::default(); self.boards.len()];
+        let mut draws = vec![Tally::default(); self.boards.len()];
+
+        let mut rng = Rng::new(seed);
+        let mut numbers = self.numbers.clone();
+        for _ in 0..games {
+            rng.shuffle(&mut numbers);
+            let game = BingoGame::new(numbers.clone(), self.boards.clone(), self.lines.clone());
+            let wins: Vec<_> = game.collect();
+
+            let first_board = wins.first().map(|win| win.board);
+            let last_board = wins.last().map(|win| win.board);
+            for (index, board) in self.boards.iter().enumerate() {
+                first[index].add(f64::from(first_board == Some(board.id)));
+                last[index].add(f64::from(last_board == Some(board.id)));
+            }
+            for win in &wins {
+                draws[win.board - 1].add((win.draw + 1) as f64);
+            }
+        }
+
+        let odds = self
+            .boards
+            .iter()
+            .enumerate()
+            .map(|(index, board)| Odds {
+                board: board.id,
+                first: first[index].estimate().unwrap(),
+                last: last[index].estimate().unwrap(),
+                draws: draws[index].estimate(),
+            })
+            .collect();
+
+        Ok(Analysis { games, seed, odds })
+    }
+}
+
+/// A table with a row per board.
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} games with seed {}, with 95% confidence intervals",
+            self.games, self.seed
+        )?;
+        writeln!(f, "board  first          last           draws to win")?;
+        for odds in &self.odds {
+            let draws = match odds.draws {
+                Some(draws) => format!("{:.1}", draws),
+                None => String::from("never wins"),
+            };
+            writeln!(
+                f,
+                "{:>5}  {}  {}  {}",
+                odds.board, odds.first, odds.last, draws
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_analysis() -> Result<()> {
+    use crate::WinPattern;
+    use aoc_core::Solution;
+
+    // the first board wins with the 1 and the second one with both 2 and 3, so the first one
+    // wins first unless the 1 comes last, and the second one after the later of 2 and 3
+    let bingo = Bingo::parse("1,2,3\n\n1 1\n\n2 3\n")?.with_patterns(vec![WinPattern::Rows])?;
+    let analysis = bingo.analyze(3000, 7)?;
+    assert_eq!(
+        analysis,
+        bingo.analyze(3000, 7)?,
+        "the same seed plays the same games"
+    );
+
+    let [one, two] = analysis.odds() else {
+        panic!("expected two boards");
+    };
+    let close = |estimate: Estimate, expected: f64| {
+        (estimate.mean - expected).abs() <= estimate.margin.max(1e-9)
+    };
+    assert!(close(one.first, 2.0 / 3.0), "{:?}", one.first);
+    assert_eq!(one.first.mean, two.last.mean);
+    assert!(close(one.draws.unwrap(), 2.0), "{:?}", one.draws);
+    assert!(close(two.draws.unwrap(), 8.0 / 3.0), "{:?}", two.draws);
+
+    assert_eq!(
+        bingo.analyze(0, 7).unwrap_err().to_string(),
+        "invalid puzzle: an analysis needs at least one game"
+    );
+
+    Ok(())
+}
@@ -1,8 +1,22 @@
-use log::{info, debug};
-use std::fmt;
-use std::str::Lines;
-
+mod adversary;
+mod analysis;
+mod pattern;
+
+pub use adversary::Witnesses;
+pub use analysis::{Analysis, Estimate, Odds};
+pub use pattern::WinPattern;
+
+use aoc_core::{normalize, parse_token, Answer, Error, Generate, Result, Rng, Solution};
+use log::{debug, info};
+use pattern::Line;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+};
+
+/// The side of the boards [`Generate`] makes up, boards read from input can have any size.
 const BOARD_DIMENSIONS: usize = 5;
+const HIGHEST_NUMBER: usize = 99;
 
 #[derive(Debug, Copy, Clone)]
 struct BingoNumber {
@@ -12,15 +26,14 @@ struct BingoNumber {
 
 impl BingoNumber {
     fn new(number: usize) -> Self {
-        BingoNumber{ number: number, marked: false }
+        BingoNumber {
+            number,
+            marked: false,
+        }
     }
 
-    fn mark_if_hit(&self, by: usize) -> Self {
-        if self.number == by {
-            BingoNumber{ number: self.number, marked: true }
-        } else {
-            *self
-        }
+    fn mark(&mut self) {
+        self.marked = true;
     }
 
     fn is_marked(&self) -> bool {
@@ -32,7 +45,7 @@ impl BingoNumber {
             false => self.number,
             _ => 0,
         }
-    } 
+    }
 }
 
 impl fmt::Display for BingoNumber {
@@ -55,52 +68,52 @@ struct Board {
 }
 
 impl Board {
-    fn new(input: Vec<&str>, id: usize) -> Self {
+    /// Reads a board from its numbered lines, every row has to be as wide as the first.
+    fn new(input: &[(usize, &str)], id: usize) -> Result<Self> {
         let mut numbers: Vec<Vec<BingoNumber>> = Vec::new();
-        
-        for line in input {
+
+        for &(line_number, line) in input {
             let mut line_numbers: Vec<BingoNumber> = Vec::new();
             for num in line.split_whitespace() {
-                let num_u8 : usize = match num.parse::<usize>() {
-                    Ok(n) => n,
-                    Err(_) => { panic!("Could not parse {:?} as u8", num) }
-                };
+                let number: usize =
+                    parse_token(line, num).map_err(|e| e.relative_to(line_number, 1))?;
 
-                line_numbers.push(BingoNumber::new(num_u8));
+                line_numbers.push(BingoNumber::new(number));
+            }
+            if let Some(first) = numbers.first() {
+                if line_numbers.len() != first.len() {
+                    return Err(Error::parse(
+                        line_number,
+                        1,
+                        format!(
+                            "expected {} numbers in every row of board {} but got {}",
+                            first.len(),
+                            id,
+                            line_numbers.len()
+                        ),
+                    ));
+                }
             }
             numbers.push(line_numbers);
         }
 
-        Board { numbers: numbers, id: id }
-    }
-
-    fn mark(&self, number: usize) -> Self {
-        let mut new_numbers: Vec<Vec<BingoNumber>> = Vec::new();
-        for line in &self.numbers {
-            let new_line: Vec<BingoNumber> = line.iter().map(|&n| n.mark_if_hit(number)).collect();
-            new_numbers.push(new_line);
-        }
-        
-        Board { numbers: new_numbers, id: self.id }
+        Ok(Board { numbers, id })
     }
 
-    fn any_line_fully_marked(&self) -> bool {
-        self.numbers.iter().any(|line| line.iter().all(|n| n.is_marked()))
+    fn rows(&self) -> usize {
+        self.numbers.len()
     }
 
-    fn any_column_fully_marked(&self) -> bool {
-        for col in 0..BOARD_DIMENSIONS {
-            if self.numbers.iter().map(|row| row[col]).all(|num: BingoNumber| num.is_marked()) {
-                return true
-            }
-        }
-        false
+    fn columns(&self) -> usize {
+        self.numbers[0].len()
     }
 
-    fn has_won(&self) -> bool {
-        self.any_line_fully_marked() 
-        || self.any_column_fully_marked()
-        || false
+    /// Whether all cells of any of `lines` are marked.
+    fn has_won(&self, lines: &[Line]) -> bool {
+        lines.iter().any(|line| {
+            line.iter()
+                .all(|&(row, column)| self.numbers[row][column].is_marked())
+        })
     }
 
     fn get_score(&self) -> usize {
@@ -112,139 +125,578 @@ impl Board {
 
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "Board {}:", self.id);
+        writeln!(f, "Board {}:", self.id)?;
         for line in &self.numbers {
             for number in line {
-                match write!(f, "{}", number) {
-                    Err(_) => (),
-                    _ => continue
-                }
+                write!(f, "{}", number)?;
             }
-            write!(f, "\n");
+            writeln!(f)?;
         }
 
         Ok(())
     }
 }
 
-#[derive(Debug)]
-struct BingoGame {
-   numbers: Vec<usize>,
-   boards: Vec<Board>,
-   last_number: usize,
-   strategy: Strategy,
+/// A board completing a row or a column.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Win {
+    /// The id of the board, boards are numbered from 1 in the order of the input.
+    pub board: usize,
+    /// The number that completed the board.
+    pub number: usize,
+    /// Where that number is in the drawn numbers, counting from 0.
+    pub draw: usize,
+    /// The sum of the unmarked numbers on the board times `number`.
+    pub score: usize,
 }
 
+/// Which win [`Bingo::play`] looks for.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Strategy {
     First,
+    /// The last board to win, boards that never win are left out.
     Last,
+    /// The nth board to win, counting from 1.
+    Nth(usize),
 }
 
-impl BingoGame {
-    fn new(numbers: Vec<usize>, boards: Vec<Board>, strategy: Strategy) -> Self {
-        let mut stacked_numbers = numbers;
-        stacked_numbers.reverse();
-        BingoGame { numbers: stacked_numbers, boards: boards, last_number: 0, strategy: strategy }
-    }
+/// Where a number is on the boards, `(board, row, column)`, boards counted from 0.
+type Occurrences = Vec<(usize, usize, usize)>;
 
-    fn find_winning_board(&mut self) -> Result<&Board, ()> {
-        while self.numbers.len() > 0 {
-            self.step();
-            let mut index = 0;
-            let mut winner: Option<&Board> = None;
-            for board in &self.boards {
-                if board.has_won() {
-                    winner = Some(&board.clone());
-                }
-                index = index + 1;
+/// Draws the numbers and yields every win in the order the boards finish, boards that
+/// finish on the same number in the order of their ids.
+///
+/// Every board keeps a counter of marked cells for each of its lines, a draw marks the
+/// cells holding the number and bumps the counters of their lines, so it costs as much as
+/// the number occurs and a board wins the moment one of its counters reaches the length
+/// of its line.
+#[derive(Debug)]
+pub struct BingoGame {
+    numbers: Vec<usize>,
+    drawn: usize,
+    boards: Vec<Board>,
+    lines: Vec<Line>,
+    /// The boards holding each number.
+    index: HashMap<usize, Occurrences>,
+    /// The lines through each cell, cells numbered row by row.
+    cell_lines: Vec<Vec<usize>>,
+    line_lengths: Vec<usize>,
+    /// The marked cells of every line of every board, board after board.
+    hits: Vec<usize>,
+    won: Vec<bool>,
+    /// How many boards have not won yet.
+    playing: usize,
+    wins: VecDeque<Win>,
+}
+
+impl BingoGame {
+    fn new(numbers: Vec<usize>, boards: Vec<Board>, lines: Vec<Line>) -> Self {
+        let columns = boards[0].columns();
+        let mut cell_lines = vec![Vec::new(); boards[0].rows() * columns];
+        for (id, line) in lines.iter().enumerate() {
+            for &(row, column) in line {
+                cell_lines[row * columns + column].push(id);
             }
-            
-            if winner.is_some() {
-                let won = winner.unwrap();
-                if self.strategy == Strategy::Last {
-                    if self.boards.len() > 1 {
-                        self.boards.remove(index);
-                        continue;
-                    }
+        }
+
+        let mut index: HashMap<usize, Occurrences> = HashMap::new();
+        for (board, numbers) in boards.iter().enumerate() {
+            for (row, line) in numbers.numbers.iter().enumerate() {
+                for (column, number) in line.iter().enumerate() {
+                    index
+                        .entry(number.number)
+                        .or_default()
+                        .push((board, row, column));
                 }
-                return Ok(won);
             }
         }
 
-        Err(())
-    }
-
-    fn run(&mut self) -> Result<(), ()> {
-        info!("Starting the game!");
-        
-        let winner = match self.find_winning_board() {
-            Ok(board) => board.clone(),
-            Err(_) => return Err(())
-        };
-
-        info!("we have a winner! it is Board {}:\n{}", winner.id, winner);
-        
-        self.calculate_score(&winner);
-
-        Ok(())
+        BingoGame {
+            numbers,
+            drawn: 0,
+            hits: vec![0; boards.len() * lines.len()],
+            won: vec![false; boards.len()],
+            playing: boards.len(),
+            boards,
+            index,
+            cell_lines,
+            line_lengths: lines.iter().map(Vec::len).collect(),
+            lines,
+            wins: VecDeque::new(),
+        }
     }
 
-    fn calculate_score(&self, winner: &Board) {
+    fn calculate_score(winner: &Board, number: usize) -> usize {
         debug!("Calculating winning score for board \n{}", winner);
         let board_score = winner.get_score();
-        let result = board_score * self.last_number;
-        info!("And the winning score = {} * {} = {}", board_score, self.last_number, result);
+        let result = board_score * number;
+        info!(
+            "And the winning score = {} * {} = {}",
+            board_score, number, result
+        );
+
+        result
     }
 
     fn step(&mut self) {
-        let number = self.numbers.pop().unwrap();
+        let number = self.numbers[self.drawn];
         info!("We drew number {}, check your boards!", &number);
-        self.boards = self.boards.iter().map(|b| b.mark(number)).collect();
-        
-        debug!("Boards:");
-        for board in &self.boards {
-            debug!("{}\n", board);
+
+        let lines = self.line_lengths.len();
+        let columns = self.boards[0].columns();
+        let mut winners = Vec::new();
+        for &(board, row, column) in self.index.get(&number).into_iter().flatten() {
+            let cell = &mut self.boards[board].numbers[row][column];
+            if self.won[board] || cell.is_marked() {
+                continue;
+            }
+            cell.mark();
+
+            for &line in &self.cell_lines[row * columns + column] {
+                let hits = &mut self.hits[board * lines + line];
+                *hits += 1;
+                if *hits == self.line_lengths[line] {
+                    winners.push(board);
+                }
+            }
+        }
+
+        // one number can complete several lines of a board
+        winners.sort_unstable();
+        winners.dedup();
+        for board in winners {
+            let winner = &self.boards[board];
+            debug_assert!(winner.has_won(&self.lines));
+            info!("we have a winner! it is Board {}:\n{}", winner.id, winner);
+            self.won[board] = true;
+            self.playing -= 1;
+            self.wins.push_back(Win {
+                board: winner.id,
+                number,
+                draw: self.drawn,
+                score: Self::calculate_score(winner, number),
+            });
         }
-        self.last_number = number;
+        self.drawn += 1;
     }
 }
 
-fn get_numbers(list: &str) -> Vec<usize> {
-    list.split(",").map(str::parse::<usize>).map(std::result::Result::unwrap).collect()
+impl Iterator for BingoGame {
+    type Item = Win;
+
+    fn next(&mut self) -> Option<Win> {
+        while self.wins.is_empty() && self.playing > 0 && self.drawn < self.numbers.len() {
+            self.step();
+        }
+        self.wins.pop_front()
+    }
 }
 
-fn get_boards(lines: Lines) -> Vec<Board> {
-    let mut boards: Vec<Board> = Vec::new();
+fn get_numbers(list: &str) -> Result<Vec<usize>> {
+    list.split(',')
+        .map(|n| parse_token(list, n.trim()))
+        .collect()
+}
 
-    let mut board_lines: Vec<&str> = lines.collect::<Vec<&str>>();
-    board_lines.retain(|&l| l.len() > 0);
+/// Reads the boards from the numbered lines following the drawn numbers, blank lines
+/// separate them. All boards have the size of the first one, rows times columns.
+fn get_boards<'a>(lines: impl Iterator<Item = (usize, &'a str)>) -> Result<Vec<Board>> {
+    let mut boards: Vec<Board> = Vec::new();
 
-    let mut board_id = 1;
-    for raw_board in board_lines.chunks(5) {
-        let mut board_list: Vec<&str> = Vec::new();
-        for line in raw_board {
-            board_list.push(line);
+    let lines: Vec<(usize, &str)> = lines.collect();
+    let raw_boards = lines
+        .split(|&(_, line)| line.trim().is_empty())
+        .filter(|raw_board| !raw_board.is_empty());
+
+    for (board_id, raw_board) in (1..).zip(raw_boards) {
+        let board = Board::new(raw_board, board_id)?;
+        if let Some(first) = boards.first() {
+            let (line, _) = raw_board[0];
+            if board.rows() != first.rows() {
+                return Err(Error::parse(
+                    line,
+                    1,
+                    format!(
+                        "expected a board of {} rows but board {} has {}",
+                        first.rows(),
+                        board_id,
+                        board.rows()
+                    ),
+                ));
+            }
+            if board.columns() != first.columns() {
+                return Err(Error::parse(
+                    line,
+                    1,
+                    format!(
+                        "expected a board of {} columns but board {} has {}",
+                        first.columns(),
+                        board_id,
+                        board.columns()
+                    ),
+                ));
+            }
         }
-        let board = Board::new(board_list, board_id);
-        board_id = board_id + 1;
         boards.push(board);
     }
 
+    if let Some(first) = boards.first() {
+        debug!(
+            "{} boards of {}x{}",
+            boards.len(),
+            first.rows(),
+            first.columns()
+        );
+    }
     debug!("Boards:");
     for board in &boards {
         debug!("{}\n", board);
     }
 
-    boards
+    Ok(boards)
+}
+
+#[derive(Clone, Debug)]
+pub struct Bingo {
+    numbers: Vec<usize>,
+    boards: Vec<Board>,
+    patterns: Vec<WinPattern>,
+    /// The lines of all patterns on boards of this size.
+    lines: Vec<Line>,
+}
+
+impl Bingo {
+    /// Plays with `patterns` instead of the puzzle's rows and columns.
+    pub fn with_patterns(self, patterns: Vec<WinPattern>) -> Result<Self> {
+        if patterns.is_empty() {
+            return Err(Error::invalid("a game needs at least one win pattern"));
+        }
+        let (rows, columns) = (self.boards[0].rows(), self.boards[0].columns());
+        let mut lines = Vec::new();
+        for pattern in &patterns {
+            lines.extend(pattern.lines(rows, columns)?);
+        }
+
+        Ok(Bingo {
+            patterns,
+            lines,
+            ..self
+        })
+    }
+
+    pub fn patterns(&self) -> &[WinPattern] {
+        &self.patterns
+    }
+
+    /// Starts a game, it goes on for as long as it is iterated.
+    pub fn wins(&self) -> BingoGame {
+        info!("Starting the game!");
+        BingoGame::new(
+            self.numbers.clone(),
+            self.boards.clone(),
+            self.lines.clone(),
+        )
+    }
+
+    /// The win `strategy` asks for.
+    pub fn play(&self, strategy: Strategy) -> Result<Win> {
+        let mut game = self.wins();
+        let win = match strategy {
+            Strategy::First => game.next(),
+            Strategy::Last => game.last(),
+            Strategy::Nth(0) => return Err(Error::invalid("wins are counted from 1")),
+            Strategy::Nth(n) => game.nth(n - 1),
+        };
+
+        win.ok_or_else(|| match strategy {
+            Strategy::Nth(n) if n > 1 => Error::invalid(format!(
+                "fewer than {} boards win with the drawn numbers",
+                n
+            )),
+            _ => Error::invalid("no board wins with the drawn numbers"),
+        })
+    }
+
+    /// Every win, in the order the boards finish.
+    pub fn ranking(&self) -> Ranking {
+        Ranking {
+            wins: self.wins().collect(),
+            boards: self.boards.len(),
+        }
+    }
+
+    /// Plays until the first and until the last board wins.
+    pub fn run(&self) -> Result<Answer> {
+        let ranking = self.ranking();
+        let (first, last) = match (ranking.wins.first(), ranking.wins.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Err(Error::invalid("no board wins with the drawn numbers")),
+        };
+
+        Ok(Answer::new(Some(first.score), Some(last.score))
+            .with("first winning board", first.board)
+            .with("last winning board", last.board))
+    }
+}
+
+/// The wins of a whole game, and how many boards played.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ranking {
+    wins: Vec<Win>,
+    boards: usize,
+}
+
+impl Ranking {
+    pub fn wins(&self) -> &[Win] {
+        &self.wins
+    }
+}
+
+/// A table with a row per win, the draws are counted from 1, followed by the boards
+/// that never win.
+impl fmt::Display for Ranking {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "rank  board  draw  number  score")?;
+        for (rank, win) in (1..).zip(&self.wins) {
+            writeln!(
+                f,
+                "{:>4}  {:>5}  {:>4}  {:>6}  {:>5}",
+                rank,
+                win.board,
+                win.draw + 1,
+                win.number,
+                win.score
+            )?;
+        }
+
+        let losers: Vec<usize> = (1..=self.boards)
+            .filter(|&id| self.wins.iter().all(|win| win.board != id))
+            .collect();
+        if !losers.is_empty() {
+            let losers: Vec<String> = losers.iter().map(usize::to_string).collect();
+            writeln!(f, "never win: {}", losers.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Solution for Bingo {
+    fn parse(input: &str) -> Result<Self> {
+        let input = normalize(input);
+        let mut lines = input.lines().enumerate().map(|(n, line)| (n + 1, line));
+        let (_, first_line) = lines
+            .next()
+            .ok_or_else(|| Error::parse(1, 1, "missing the drawn numbers"))?;
+        let numbers = get_numbers(first_line)?;
+        let boards = get_boards(lines)?;
+        if boards.is_empty() {
+            return Err(Error::invalid("there are no boards to play on"));
+        }
+
+        Bingo {
+            numbers,
+            boards,
+            patterns: Vec::new(),
+            lines: Vec::new(),
+        }
+        .with_patterns(WinPattern::PUZZLE.to_vec())
+    }
+
+    fn part1(&self) -> Result<Option<usize>> {
+        Ok(Some(self.play(Strategy::First)?.score))
+    }
+
+    fn part2(&self) -> Result<Option<usize>> {
+        Ok(Some(self.play(Strategy::Last)?.score))
+    }
+
+    fn answer(&self) -> Result<Answer> {
+        self.run()
+    }
+}
+
+pub fn play_bingo(input: String, strategy: Strategy, patterns: &[WinPattern]) -> Result<usize> {
+    let bingo = Bingo::parse(&input)?.with_patterns(patterns.to_vec())?;
+    Ok(bingo.play(strategy)?.score)
 }
 
-pub fn play_bingo(input: String, strategy: Strategy) -> Result<(), ()> {
-    let mut lines = input.lines();
-    let numbers = get_numbers(lines.next().unwrap());
-    let boards = get_boards(lines);
+/// `size` is the number of boards, every number up to 99 is drawn so every board wins.
+impl Generate for Bingo {
+    const DEFAULT_SIZE: usize = 100;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut numbers: Vec<usize> = (0..=HIGHEST_NUMBER).collect();
+        rng.shuffle(&mut numbers);
+        let drawn: Vec<String> = numbers.iter().map(usize::to_string).collect();
+        let mut output = drawn.join(",");
+        output.push('\n');
+
+        for _ in 0..size {
+            rng.shuffle(&mut numbers);
+            output.push('\n');
+            for row in numbers[..BOARD_DIMENSIONS * BOARD_DIMENSIONS].chunks(BOARD_DIMENSIONS) {
+                let row: Vec<String> = row.iter().map(|n| format!("{:>2}", n)).collect();
+                output.push_str(&row.join(" "));
+                output.push('\n');
+            }
+        }
 
-    let mut game = BingoGame::new(numbers, boards, strategy);
-    
-    game.run()
-}
\ No newline at end of file
+        output
+    }
+}
+
+#[test]
+fn test_generated_games_match_marked_boards() -> Result<()> {
+    // a board wins with the last number of its line drawn first, every number is drawn
+    aoc_core::check_generated(1..=10, |bingo: &Bingo| {
+        let drawn = |number: usize| bingo.numbers.iter().position(|&n| n == number).unwrap();
+        let wins: Vec<(usize, usize, usize)> = bingo
+            .boards
+            .iter()
+            .enumerate()
+            .map(|(index, board)| {
+                let draws: Vec<Vec<usize>> = board
+                    .numbers
+                    .iter()
+                    .map(|row| row.iter().map(|cell| drawn(cell.number)).collect())
+                    .collect();
+                let rows = draws.iter().map(|row| *row.iter().max().unwrap());
+                let columns = (0..BOARD_DIMENSIONS)
+                    .map(|column| draws.iter().map(|row| row[column]).max().unwrap());
+                let draw = rows.chain(columns).min().unwrap();
+                let unmarked: usize = board
+                    .numbers
+                    .iter()
+                    .flatten()
+                    .filter(|cell| drawn(cell.number) > draw)
+                    .map(|cell| cell.number)
+                    .sum();
+                (draw, index, unmarked * bingo.numbers[draw])
+            })
+            .collect();
+        [wins.iter().min(), wins.iter().max()].map(|win| win.map(|&(_, _, score)| score))
+    })
+}
+
+#[test]
+fn test_wins() -> Result<()> {
+    let bingo = Bingo::parse(include_str!("../example.txt"))?;
+
+    let wins: Vec<(usize, usize, usize, usize)> = bingo
+        .wins()
+        .map(|win| (win.board, win.number, win.draw, win.score))
+        .collect();
+    assert_eq!(
+        wins,
+        [(3, 24, 11, 4512), (1, 16, 13, 2192), (2, 13, 14, 1924)]
+    );
+
+    assert_eq!(bingo.play(Strategy::Last)?.score, 1924);
+    assert_eq!(bingo.play(Strategy::Nth(2))?.board, 1);
+    assert_eq!(
+        bingo.play(Strategy::Nth(4)).unwrap_err().to_string(),
+        "invalid puzzle: fewer than 4 boards win with the drawn numbers"
+    );
+
+    // only the third board wins before the draws run out
+    let input =
+        include_str!("../example.txt").replacen(",10,16,13,6,15,25,12,22,18,20,8,19,3,26,1", "", 1);
+    let ranking = Bingo::parse(&input)?.ranking();
+    assert_eq!(
+        ranking.to_string(),
+        "rank  board  draw  number  score\n   1      3    12      24   4512\nnever win: 1, 2\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_board_sizes() -> Result<()> {
+    let wins = |input: &str| -> Result<Vec<(usize, usize, usize)>> {
+        Ok(Bingo::parse(input)?
+            .wins()
+            .map(|win| (win.board, win.draw, win.score))
+            .collect())
+    };
+
+    let three = "1,5,9,2,3\n\n1 2 3\n4 5 6\n7 8 9\n\n9 8 7\n6 5 4\n3 2 1\n";
+    assert_eq!(wins(three)?, [(1, 4, 75), (2, 4, 75)]);
+
+    let rectangle = "4,3,2,1\n\n1 2 3 4\n5 6 7 8\n";
+    assert_eq!(wins(rectangle)?, [(1, 3, 26)]);
+
+    let rows: Vec<String> = (0..7)
+        .map(|row| {
+            let row: Vec<String> = (1..=7).map(|n| (row * 7 + n).to_string()).collect();
+            row.join(" ")
+        })
+        .collect();
+    let seven = format!("7,14,21,28,35,42,49\n\n{}\n", rows.join("\n"));
+    assert_eq!(wins(&seven)?, [(1, 6, (1225 - 196) * 49)]);
+
+    for (input, error) in [
+        (
+            "1\n\n1 2\n3\n",
+            "line 4, column 1: expected 2 numbers in every row of board 1 but got 1",
+        ),
+        (
+            "1\n\n1 2\n3 4\n\n1 2\n",
+            "line 6, column 1: expected a board of 2 rows but board 2 has 1",
+        ),
+        (
+            "1\n\n1 2\n3 4\n\n1 2 3\n4 5 6\n",
+            "line 6, column 1: expected a board of 2 columns but board 2 has 3",
+        ),
+    ] {
+        assert_eq!(Bingo::parse(input).unwrap_err().to_string(), error);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_win_patterns() -> Result<()> {
+    let input = "7,1,5,9,2,3,4\n\n1 2 3\n4 5 6\n7 8 9\n\n9 8 7\n6 5 4\n3 2 1\n";
+    let play = |patterns: &[WinPattern]| -> Result<(usize, usize)> {
+        let bingo = Bingo::parse(input)?.with_patterns(patterns.to_vec())?;
+        let win = bingo.play(Strategy::First)?;
+        Ok((win.board, win.draw))
+    };
+
+    assert_eq!(play(&WinPattern::PUZZLE)?, (1, 5));
+    // 1, 5 and 9 are the diagonal of the first board
+    assert_eq!(play(&[WinPattern::Diagonals])?, (1, 3));
+    // 7, 1, 9 and 3 are the corners of both
+    assert_eq!(play(&[WinPattern::Corners])?, (1, 5));
+    assert_eq!(play(&[WinPattern::X])?, (1, 5));
+    assert_eq!(
+        play(&WinPattern::masks("000\n000\n010\n")?)?,
+        (2, 4),
+        "the first board's 8 is never drawn, the second board's 2 is"
+    );
+    assert_eq!(
+        play(&[WinPattern::Blackout]).unwrap_err().to_string(),
+        "invalid puzzle: no board wins with the drawn numbers"
+    );
+
+    assert_eq!(
+        play_bingo(String::from(input), Strategy::Last, &[WinPattern::Rows])?,
+        (8 + 6 + 4) * 3
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_repeated_draws() -> Result<()> {
+    // drawing 1 again does not complete the row, and the won first board is not scored twice
+    let bingo = Bingo::parse("1,1,3,2,4\n\n1 2\n5 6\n\n3 4\n7 8\n")?;
+    let wins: Vec<(usize, usize, usize)> = bingo
+        .wins()
+        .map(|win| (win.board, win.draw, win.score))
+        .collect();
+    assert_eq!(wins, [(1, 3, 22), (2, 4, 60)]);
+
+    Ok(())
+}
@@ -0,0 +1,239 @@
+//! Ways to win a game of bingo, the puzzle's rows and columns and a few variants.
+
+use std::{fmt, str::FromStr};
+
+use aoc_core::{Error, Result};
+
+/// Cells of a board, `(row, column)`, that win once all of their numbers are marked.
+pub(crate) type Line = Vec<(usize, usize)>;
+
+/// A way to win, a board wins as soon as one of the lines of any of its patterns is marked.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WinPattern {
+    /// Any full row.
+    Rows,
+    /// Any full column.
+    Columns,
+    /// Either diagonal of a square board.
+    Diagonals,
+    /// All four corners.
+    Corners,
+    /// Both diagonals of a square board at once.
+    X,
+    /// Every number of the board.
+    Blackout,
+    /// The cells set in the mask, which has to be as big as the boards.
+    Mask(Vec<Vec<bool>>),
+}
+
+impl WinPattern {
+    /// The patterns of the puzzle, rows and columns.
+    pub const PUZZLE: [WinPattern; 2] = [WinPattern::Rows, WinPattern::Columns];
+
+    /// Reads masks of `1`s and `0`s, one row per line, masks are separated by blank lines.
+    pub fn masks(input: &str) -> Result<Vec<WinPattern>> {
+        let mut masks = Vec::new();
+        let mut mask: Vec<Vec<bool>> = Vec::new();
+        let mut start = 0;
+
+        for (n, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                if !mask.is_empty() {
+                    masks.push(Self::mask(std::mem::take(&mut mask), start)?);
+                }
+                continue;
+            }
+            if mask.is_empty() {
+                start = n + 1;
+            }
+
+            let mut row = Vec::new();
+            for (column, c) in line.chars().enumerate() {
+                match c {
+                    '1' => row.push(true),
+                    '0' => row.push(false),
+                    c if c.is_whitespace() => {}
+                    c => {
+                        return Err(Error::parse(
+                            n + 1,
+                            column + 1,
+                            format!("expected '0' or '1' but got {:?}", c),
+                        ))
+                    }
+                }
+            }
+            if let Some(first) = mask.first() {
+                if row.len() != first.len() {
+                    return Err(Error::parse(
+                        n + 1,
+                        1,
+                        format!(
+                            "expected {} cells in every row of the mask but got {}",
+                            first.len(),
+                            row.len()
+                        ),
+                    ));
+                }
+            }
+            mask.push(row);
+        }
+        if !mask.is_empty() {
+            masks.push(Self::mask(mask, start)?);
+        }
+
+        if masks.is_empty() {
+            return Err(Error::invalid("there are no masks"));
+        }
+        Ok(masks)
+    }
+
+    /// A mask read from the lines from `line` on, it has to set at least one cell.
+    fn mask(mask: Vec<Vec<bool>>, line: usize) -> Result<WinPattern> {
+        if !mask.iter().flatten().any(|&cell| cell) {
+            return Err(Error::parse(line, 1, "the mask sets no cell"));
+        }
+        Ok(WinPattern::Mask(mask))
+    }
+
+    /// The lines of this pattern on boards of `rows` times `columns`.
+    pub(crate) fn lines(&self, rows: usize, columns: usize) -> Result<Vec<Line>> {
+        let square = || match rows == columns {
+            true => Ok(rows),
+            false => Err(Error::invalid(format!(
+                "the {} pattern needs a square board but the boards are {}x{}",
+                self, rows, columns
+            ))),
+        };
+        let diagonal = |side: usize| (0..side).map(|i| (i, i)).collect::<Line>();
+        let anti_diagonal = |side: usize| (0..side).map(|i| (i, side - 1 - i)).collect::<Line>();
+
+        Ok(match self {
+            WinPattern::Rows => (0..rows)
+                .map(|row| (0..columns).map(|column| (row, column)).collect())
+                .collect(),
+            WinPattern::Columns => (0..columns)
+                .map(|column| (0..rows).map(|row| (row, column)).collect())
+                .collect(),
+            WinPattern::Diagonals => {
+                let side = square()?;
+                vec![diagonal(side), anti_diagonal(side)]
+            }
+            WinPattern::Corners => {
+                let mut corners = vec![
+                    (0, 0),
+                    (0, columns - 1),
+                    (rows - 1, 0),
+                    (rows - 1, columns - 1),
+                ];
+                corners.sort_unstable();
+                corners.dedup();
+                vec![corners]
+            }
+            WinPattern::X => {
+                let side = square()?;
+                let mut x = diagonal(side);
+                // the middle of an odd board is on both diagonals
+                x.extend(anti_diagonal(side).into_iter().filter(|&(i, j)| i != j));
+                vec![x]
+            }
+            WinPattern::Blackout => {
+                vec![(0..rows)
+                    .flat_map(|row| (0..columns).map(move |column| (row, column)))
+                    .collect()]
+            }
+            WinPattern::Mask(mask) => {
+                if mask.len() != rows || mask[0].len() != columns {
+                    return Err(Error::invalid(format!(
+                        "a {}x{} mask does not fit boards of {}x{}",
+                        mask.len(),
+                        mask[0].len(),
+                        rows,
+                        columns
+                    )));
+                }
+                vec![(0..rows)
+                    .flat_map(|row| (0..columns).map(move |column| (row, column)))
+                    .filter(|&(row, column)| mask[row][column])
+                    .collect()]
+            }
+        })
+    }
+}
+
+impl FromStr for WinPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "rows" => Ok(WinPattern::Rows),
+            "columns" => Ok(WinPattern::Columns),
+            "diagonals" => Ok(WinPattern::Diagonals),
+            "corners" => Ok(WinPattern::Corners),
+            "x" => Ok(WinPattern::X),
+            "blackout" => Ok(WinPattern::Blackout),
+            other => Err(format!(
+                "expected rows, columns, diagonals, corners, x or blackout but got {:?}",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for WinPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WinPattern::Rows => write!(f, "rows"),
+            WinPattern::Columns => write!(f, "columns"),
+            WinPattern::Diagonals => write!(f, "diagonals"),
+            WinPattern::Corners => write!(f, "corners"),
+            WinPattern::X => write!(f, "x"),
+            WinPattern::Blackout => write!(f, "blackout"),
+            WinPattern::Mask(_) => write!(f, "mask"),
+        }
+    }
+}
+
+#[test]
+fn test_lines() -> Result<()> {
+    assert_eq!(
+        WinPattern::Rows.lines(2, 3)?,
+        [[(0, 0), (0, 1), (0, 2)], [(1, 0), (1, 1), (1, 2)]]
+    );
+    assert_eq!(
+        WinPattern::Diagonals.lines(3, 3)?,
+        [[(0, 0), (1, 1), (2, 2)], [(0, 2), (1, 1), (2, 0)]]
+    );
+    assert_eq!(
+        WinPattern::X.lines(3, 3)?,
+        [[(0, 0), (1, 1), (2, 2), (0, 2), (2, 0)]]
+    );
+    assert_eq!(WinPattern::Corners.lines(1, 3)?, [[(0, 0), (0, 2)]]);
+    assert_eq!(
+        WinPattern::X.lines(2, 3).unwrap_err().to_string(),
+        "invalid puzzle: the x pattern needs a square board but the boards are 2x3"
+    );
+
+    let masks = WinPattern::masks("010\n111\n010\n\n1 0 0\n0 0 0\n")?;
+    assert_eq!(
+        masks[0].lines(3, 3)?,
+        [[(0, 1), (1, 0), (1, 1), (1, 2), (2, 1)]]
+    );
+    assert_eq!(masks[1].lines(2, 3)?, [[(0, 0)]]);
+    assert_eq!(
+        masks[1].lines(3, 3).unwrap_err().to_string(),
+        "invalid puzzle: a 2x3 mask does not fit boards of 3x3"
+    );
+
+    for (input, error) in [
+        (
+            "01\n1\n",
+            "line 2, column 1: expected 2 cells in every row of the mask but got 1",
+        ),
+        ("01\n\n00\n00\n", "line 3, column 1: the mask sets no cell"),
+        ("0x\n", "line 1, column 2: expected '0' or '1' but got 'x'"),
+    ] {
+        assert_eq!(WinPattern::masks(input).unwrap_err().to_string(), error);
+    }
+
+    Ok(())
+}
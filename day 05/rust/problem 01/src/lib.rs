@@ -1,7 +1,7 @@
+use aoc_core::{column_of, normalize, parse_token, Answer, Error, Generate, Part, Rng, Solution};
+use aoc_grid::{scale, Grid, Render, Rgb};
 use log::{debug, info};
 use std::fmt;
-use std::fmt::Write as FmtWrite;
-use std::num::ParseIntError;
 use std::str::FromStr;
 
 const DIM: usize = 1000;
@@ -26,16 +26,20 @@ impl Point2D {
 }
 
 impl FromStr for Point2D {
-    type Err = ParseIntError;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let coords: Vec<&str> = s
-            .trim_matches(|p| p == '(' || p == ')' || p == ' ')
-            .split(',')
-            .collect();
-
-        let x_fromstr = coords[0].parse::<isize>()?;
-        let y_fromstr = coords[1].parse::<isize>()?;
+        let coords = s.trim_matches(|p| p == '(' || p == ')' || p == ' ');
+        let (x, y) = coords.split_once(',').ok_or_else(|| {
+            Error::parse(
+                1,
+                column_of(s, coords),
+                format!("expected a point like 0,9 but got {:?}", coords),
+            )
+        })?;
+
+        let x_fromstr = parse_token::<isize>(s, x)?;
+        let y_fromstr = parse_token::<isize>(s, y)?;
 
         Ok(Point2D::new(x_fromstr, y_fromstr))
     }
@@ -74,6 +78,19 @@ impl Line {
         !self.is_simple()
     }
 
+    fn check(&self) -> Result<(), String> {
+        let on_board =
+            |p: Point2D| p.x >= 0 && p.y >= 0 && p.x < DIM as isize && p.y < DIM as isize;
+        if !on_board(self.from) || !on_board(self.to) {
+            return Err(format!("{} leaves the {}x{} board", self, DIM, DIM));
+        }
+        if self.is_diagonal() && (self.from.x - self.to.x).abs() != (self.from.y - self.to.y).abs()
+        {
+            return Err(format!("{} is neither straight nor at 45 degrees", self));
+        }
+        Ok(())
+    }
+
     fn get_all_points(&self) -> Vec<Point2D> {
         let mut result = Vec::new();
         if self.is_horizontal() {
@@ -107,13 +124,13 @@ impl Line {
             for x in lower_bound.x..=upper_bound.x {
                 result.push(Point2D::new(x, y));
                 if y_step_up {
-                    y = y + 1;
+                    y += 1;
                 } else {
                     if y == 0 {
-                        break
+                        break;
                     }
                     if y > 0 {
-                        y = y - 1;
+                        y -= 1;
                     }
                 }
             }
@@ -130,13 +147,23 @@ impl fmt::Display for Line {
 }
 
 impl FromStr for Line {
-    type Err = ParseIntError;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let coords: Vec<&str> = s.trim_matches(|p| p == ' ').split("->").collect();
-
-        let point_from: Point2D = coords[0].parse()?;
-        let point_to: Point2D = coords[1].parse()?;
+        let (from, to) = s.split_once("->").ok_or_else(|| {
+            Error::parse(
+                1,
+                1,
+                format!("expected a line like 0,9 -> 5,9 but got {:?}", s),
+            )
+        })?;
+
+        let point_from: Point2D = from
+            .parse()
+            .map_err(|e: Error| e.relative_to(1, column_of(s, from)))?;
+        let point_to: Point2D = to
+            .parse()
+            .map_err(|e: Error| e.relative_to(1, column_of(s, to)))?;
 
         Ok(Line::new(point_from, point_to))
     }
@@ -159,61 +186,47 @@ impl From<[isize; 4]> for Line {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 struct Board {
-    items: [u8; DIM * DIM],
+    items: Grid<u8>,
 }
 
 impl Board {
     fn new() -> Self {
         Board {
-            items: [0; DIM * DIM],
+            items: Grid::new(DIM, DIM, 0),
         }
     }
 
-    fn position(&self, row: usize, col: usize) -> usize {
-        DIM * row + col
-    }
-
     fn apply(&mut self, line: Line) {
         debug!("Applying {}:", line);
         let get_all_points = line.get_all_points();
         for point in get_all_points {
             debug!("- {}", point);
-            let pos = self.position(
+            let position = (
                 usize::try_from(point.y).unwrap(),
                 usize::try_from(point.x).unwrap(),
             );
-            self.items[pos] = self.items[pos] + 1;
+            self.items[position] += 1;
         }
 
         debug!("{}", self);
     }
 
     fn count_crossings(&self) -> usize {
-        self.items.iter().filter(|&n| *n > 1).count()
+        self.items.values().filter(|&n| *n > 1).count()
     }
 }
 
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut output = String::new();
-        writeln!(&mut output)?;
-        for line in 0..DIM {
-            for cols in 0..DIM {
-                let item = self.items[self.position(line, cols)];
-                let item_as_str = match item {
-                    0 => String::from("."),
-                    _ => format!("{}", u8::min(item, 9)),
-                };
-                write!(&mut output, "{}", item_as_str)?;
-            }
-            writeln!(&mut output)?;
-        }
-        writeln!(&mut output)?;
+        let hits = self.items.map(|&item| match item {
+            0 => '.',
+            _ => char::from(b'0' + u8::min(item, 9)),
+        });
 
-        write!(f, "{}", output)
-        // write!(f, "{}", 2)
+        writeln!(f)?;
+        writeln!(f, "{}", hits)
     }
 }
 
@@ -224,24 +237,28 @@ pub struct Simulation {
 }
 
 impl Simulation {
-    pub fn new(input: String) -> Simulation {
-        Simulation {
-            lines: input
-                .lines()
-                .map(Line::from_str)
-                .map(|res| res.unwrap())
-                .collect(),
-            board: Board::new(),
+    pub fn new(input: String) -> Result<Simulation, Error> {
+        let mut lines = Vec::new();
+        for (n, raw_line) in input
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+        {
+            let line = Line::from_str(raw_line).map_err(|e| e.relative_to(n + 1, 1))?;
+            line.check()
+                .map_err(|message| Error::invalid(format!("line {}: {}", n + 1, message)))?;
+            lines.push(line);
         }
+
+        Ok(Simulation {
+            lines,
+            board: Board::new(),
+        })
     }
 
     fn steps(&mut self) {
-        loop {
-            if let Some(line) = self.lines.pop() {
-                self.step(line);
-            } else {
-                break;
-            }
+        while let Some(line) = self.lines.pop() {
+            self.step(line);
         }
     }
 
@@ -251,19 +268,36 @@ impl Simulation {
         debug!("step ... done");
     }
 
-    pub fn run(&mut self) -> Result<(), ()> {
+    fn board(&self, include_diagonals: bool) -> Board {
+        let mut board = Board::new();
+        self.lines
+            .iter()
+            .filter(|line| include_diagonals || line.is_simple())
+            .for_each(|line| board.apply(*line));
+
+        board
+    }
+
+    fn count_crossings(&self, include_diagonals: bool) -> usize {
+        self.board(include_diagonals).count_crossings()
+    }
+
+    pub fn run(&mut self) -> Result<Answer, Error> {
         info!("Running simulation now");
+        let lines = self.lines.len();
+        let diagonals = self.lines.iter().filter(|line| line.is_diagonal()).count();
+        let simple_crossings = self.count_crossings(false);
 
         self.steps();
         info!("Board after applying vectors:\n{}", self.board);
 
-        info!(
-            "{} points where vectors cross",
-            self.board.count_crossings()
-        );
+        let crossings = self.board.count_crossings();
+        info!("{} points where vectors cross", crossings);
 
         info!("Simulation is done");
-        Ok(())
+        Ok(Answer::new(Some(simple_crossings), Some(crossings))
+            .with("lines", lines)
+            .with("diagonal lines", diagonals))
     }
 }
 
@@ -271,7 +305,7 @@ impl fmt::Display for Simulation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let steps_left = self.lines.len();
         let status = match steps_left {
-            0 => format!("Simulation ended, see result below"),
+            0 => "Simulation ended, see result below".to_string(),
             a => format!("{} steps left", a),
         };
 
@@ -282,3 +316,93 @@ impl fmt::Display for Simulation {
         write!(f, "{}", status)
     }
 }
+
+impl Solution for Simulation {
+    fn parse(input: &str) -> Result<Self, Error> {
+        Simulation::new(normalize(input))
+    }
+
+    fn part1(&self) -> Result<Option<usize>, Error> {
+        Ok(Some(self.count_crossings(false)))
+    }
+
+    fn part2(&self) -> Result<Option<usize>, Error> {
+        Ok(Some(self.count_crossings(true)))
+    }
+
+    fn answer(&self) -> Result<Answer, Error> {
+        self.clone().run()
+    }
+}
+
+impl Render for Simulation {
+    fn render(&self, part: Part) -> Grid<Rgb> {
+        let board = self.board(part == Part::Two);
+        let max = board.items.values().copied().max().unwrap_or(0);
+
+        board.items.map(|&hits| match hits {
+            0 => Rgb::BLACK,
+            _ => scale(hits as usize, max as usize),
+        })
+    }
+}
+
+/// `size` is the number of lines, about a third of them diagonal.
+impl Generate for Simulation {
+    const DEFAULT_SIZE: usize = 500;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let last = DIM as isize - 1;
+        let mut output = String::new();
+
+        for _ in 0..size {
+            let from = Point2D::new(
+                rng.range(0..=DIM - 1) as isize,
+                rng.range(0..=DIM - 1) as isize,
+            );
+            let to = match rng.range(0..=2) {
+                0 => Point2D::new(rng.range(0..=DIM - 1) as isize, from.y),
+                1 => Point2D::new(from.x, rng.range(0..=DIM - 1) as isize),
+                _ => {
+                    let (dx, dy) = (
+                        if rng.chance(0.5) { 1 } else { -1 },
+                        if rng.chance(0.5) { 1 } else { -1 },
+                    );
+                    // as far as the line can go without leaving the board
+                    let room = |p: isize, d: isize| if d > 0 { last - p } else { p };
+                    let steps = rng
+                        .range(0..=isize::min(room(from.x, dx), room(from.y, dy)) as usize)
+                        as isize;
+                    Point2D::new(from.x + dx * steps, from.y + dy * steps)
+                }
+            };
+            output.push_str(&format!("{},{} -> {},{}\n", from.x, from.y, to.x, to.y));
+        }
+
+        output
+    }
+}
+
+#[test]
+fn test_generated_crossings_match_counted_points() -> Result<(), Error> {
+    // walks every line point by point and counts the points covered at least twice
+    aoc_core::check_generated(0..=50, |simulation: &Simulation| {
+        let crossings = |diagonals: bool| {
+            let mut covered = std::collections::HashMap::new();
+            for Line { from, to } in &simulation.lines {
+                let (x, y) = ((to.x - from.x).signum(), (to.y - from.y).signum());
+                if x != 0 && y != 0 && !diagonals {
+                    continue;
+                }
+                let steps = isize::max((to.x - from.x).abs(), (to.y - from.y).abs());
+                for step in 0..=steps {
+                    *covered
+                        .entry((from.x + x * step, from.y + y * step))
+                        .or_insert(0) += 1;
+                }
+            }
+            covered.values().filter(|&&count| count > 1).count()
+        };
+        [Some(crossings(false)), Some(crossings(true))]
+    })
+}
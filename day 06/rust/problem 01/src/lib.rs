@@ -1,12 +1,13 @@
 use std::collections::BTreeMap;
 use std::fmt;
-use std::str::FromStr;
 
+use aoc_core::{normalize, parse_token, Answer, Error, Generate, Result, Rng, Solution};
 use log::{debug, info};
 
 const FISH_RESTART_TIMER: u8 = 6;
 const FISH_SPAWN_TIMER: u8 = 8;
 const RUN_FOR_DAYS: u16 = 256;
+const SHORT_RUN_FOR_DAYS: u16 = 80;
 
 #[derive(Clone, Debug)]
 pub struct Simulation {
@@ -15,36 +16,52 @@ pub struct Simulation {
 }
 
 impl Simulation {
-    pub fn new(input: String) -> Self {
+    pub fn new(input: String) -> Result<Self> {
         let mut fishes = BTreeMap::new();
-        for mut item in input.split_terminator(",") {
-            item = item.trim();
-            let num = u8::from_str(item).unwrap();
-            fishes.entry(num).and_modify(|f| *f += 1).or_insert(1);
+        for (n, line) in input.lines().enumerate() {
+            for mut item in line.split_terminator(',') {
+                item = item.trim();
+                let num: u8 = parse_token(line, item).map_err(|e| e.relative_to(n + 1, 1))?;
+                fishes.entry(num).and_modify(|f| *f += 1).or_insert(1);
+            }
+        }
+        if fishes.is_empty() {
+            return Err(Error::invalid("there are no lanternfish"));
+        }
+        Ok(Simulation { days: 0, fishes })
+    }
+
+    fn amount_fishes_after(&self, days: u16) -> usize {
+        let mut simulation = self.clone();
+        for _ in 0..days {
+            simulation.age_one_day();
         }
-        Simulation { days: 0, fishes }
+        simulation.amount_fishes()
     }
 
-    pub fn run(&mut self) -> Result<(), ()> {
+    pub fn run(&mut self) -> Result<Answer> {
         info!("Running Simulation");
+        let initial_fishes = self.amount_fishes();
+        let mut short_run_fishes = None;
         for day in 0..RUN_FOR_DAYS {
             self.days = day;
             debug!("{}", self);
             self.age_one_day();
             // info!("Day {}", day);
+            if day + 1 == SHORT_RUN_FOR_DAYS {
+                short_run_fishes = Some(self.summary(SHORT_RUN_FOR_DAYS));
+            }
         }
         self.days = RUN_FOR_DAYS;
         debug!("{}", self);
-        self.print_summary();
-        Ok(())
+        let fishes = self.summary(RUN_FOR_DAYS);
+        Ok(Answer::new(short_run_fishes, Some(fishes)).with("initial fishes", initial_fishes))
     }
 
-    fn print_summary(&self) {
-        info!(
-            "After {} days, there are {} fishes!",
-            RUN_FOR_DAYS,
-            self.amount_fishes()
-        );
+    fn summary(&self, days: u16) -> usize {
+        let fishes = self.amount_fishes();
+        info!("After {} days, there are {} fishes!", days, fishes);
+        fishes
     }
 
     fn amount_fishes(&self) -> usize {
@@ -93,3 +110,62 @@ impl fmt::Display for Simulation {
         }
     }
 }
+
+impl Solution for Simulation {
+    fn parse(input: &str) -> Result<Self> {
+        Simulation::new(normalize(input))
+    }
+
+    fn part1(&self) -> Result<Option<usize>> {
+        Ok(Some(self.amount_fishes_after(SHORT_RUN_FOR_DAYS)))
+    }
+
+    fn part2(&self) -> Result<Option<usize>> {
+        Ok(Some(self.amount_fishes_after(RUN_FOR_DAYS)))
+    }
+
+    fn answer(&self) -> Result<Answer> {
+        self.clone().run()
+    }
+}
+
+/// `size` is the number of lanternfish, their timers start between 1 and 5.
+impl Generate for Simulation {
+    const DEFAULT_SIZE: usize = 300;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let timers: Vec<String> = (0..size).map(|_| rng.range(1..=5).to_string()).collect();
+        timers.join(",") + "\n"
+    }
+}
+
+#[test]
+fn test_generated_schools_match_single_fishes() -> Result<()> {
+    aoc_core::check_generated(1..=10, |simulation: &Simulation| {
+        // ages every fish on its own
+        let mut fishes: Vec<u8> = simulation
+            .fishes
+            .iter()
+            .flat_map(|(&timer, &count)| std::iter::repeat_n(timer, count))
+            .collect();
+        for _ in 0..SHORT_RUN_FOR_DAYS {
+            let spawned = fishes.iter().filter(|&&timer| timer == 0).count();
+            for timer in fishes.iter_mut() {
+                *timer = timer.checked_sub(1).unwrap_or(FISH_RESTART_TIMER);
+            }
+            fishes.extend(std::iter::repeat_n(FISH_SPAWN_TIMER, spawned));
+        }
+
+        // far too many fishes for that in the long run, counts them by timer instead
+        let mut timers = [0; FISH_SPAWN_TIMER as usize + 1];
+        for (&timer, &count) in &simulation.fishes {
+            timers[timer as usize] += count;
+        }
+        for _ in 0..RUN_FOR_DAYS {
+            timers.rotate_left(1);
+            timers[FISH_RESTART_TIMER as usize] += timers[FISH_SPAWN_TIMER as usize];
+        }
+
+        [Some(fishes.len()), Some(timers.iter().sum())]
+    })
+}
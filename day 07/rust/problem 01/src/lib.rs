@@ -1,8 +1,12 @@
-use std::{collections::BTreeMap, fmt::{self, Debug}};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug},
+};
 // extern crate conv;
 // use conv::*;
 
-use log::{info, debug};
+use aoc_core::{normalize, parse_token, Answer, Error, Generate, Result, Rng, Solution};
+use log::{debug, info};
 
 type Test = usize;
 type Counter = usize;
@@ -10,10 +14,10 @@ type Counter = usize;
 struct Calculation(BTreeMap<Test, Counter>);
 
 impl Calculation {
-    fn with_simple_diff(numbers: &Vec<usize>) -> Self {
+    fn with_simple_diff(numbers: &[usize]) -> Self {
         let mut collection = BTreeMap::new();
-        let (min, max) = Self::min_max(&numbers);
-        for num in min..max {
+        let (min, max) = Self::min_max(numbers);
+        for num in min..=max {
             // no need to de-dup
             let mut result: usize = 0;
             for elem in numbers {
@@ -23,13 +27,13 @@ impl Calculation {
             debug!("simple calc: {} -> {}", num, result);
         }
 
-        Calculation { 0: collection }
+        Calculation(collection)
     }
 
-    fn with_linear_diff(numbers: &Vec<usize>) -> Self {
+    fn with_linear_diff(numbers: &[usize]) -> Self {
         let mut collection = BTreeMap::new();
-        let (min, max) = Self::min_max(&numbers);
-        for num in min..max {
+        let (min, max) = Self::min_max(numbers);
+        for num in min..=max {
             // no need to de-dup
             let mut result: usize = 0;
             for elem in numbers {
@@ -38,7 +42,7 @@ impl Calculation {
             collection.insert(num, result);
         }
 
-        Calculation { 0: collection }
+        Calculation(collection)
     }
 
     fn minimum(&self) -> (Test, Counter) {
@@ -46,26 +50,18 @@ impl Calculation {
         (*entry.0, *entry.1)
     }
 
-    fn min_max(numbers: &Vec<usize>) -> (Test, Test) {
+    fn min_max(numbers: &[usize]) -> (Test, Test) {
         (numbers[0], numbers[numbers.len() - 1])
     }
 
     fn difference(a: usize, b: usize) -> usize {
-        if a > b {
-            a - b
-        } else {
-            b - a
-        }
+        a.abs_diff(b)
     }
 
     fn linear_difference(a: usize, b: usize) -> usize {
-        let min = usize::min(a, b);
-        let max = usize::max(a, b);
-        let mut counter = 0;
-        for i in min..=max {
-            counter += Self::difference(min, i);
-        }
-        counter
+        // 1 + 2 + ... + n
+        let steps = Self::difference(a, b);
+        steps * (steps + 1) / 2
     }
 }
 
@@ -75,18 +71,21 @@ pub struct Simulation {
 }
 
 impl Simulation {
-    pub fn new(input: String) -> Self {
-        let mut numbers: Vec<usize> = input
-            .split(",")
-            .map(str::trim)
-            .map(|n| usize::from_str_radix(n, 10))
-            .map(|e| e.unwrap())
-            .collect();
+    pub fn new(input: String) -> Result<Self> {
+        let mut numbers: Vec<usize> = Vec::new();
+        for (n, line) in input.lines().enumerate() {
+            for item in line.split_terminator(',').map(str::trim) {
+                numbers.push(parse_token(line, item).map_err(|e| e.relative_to(n + 1, 1))?);
+            }
+        }
+        if numbers.is_empty() {
+            return Err(Error::invalid("there are no crab positions"));
+        }
         numbers.sort();
-        Simulation { numbers }
+        Ok(Simulation { numbers })
     }
 
-    pub fn run(&self) {
+    pub fn run(&self) -> Answer {
         info!("Starting calculation");
         let calc_simple = Calculation::with_simple_diff(&self.numbers);
         let (s_test, s_counter) = calc_simple.minimum();
@@ -95,6 +94,9 @@ impl Simulation {
         let (l_test, l_counter) = calc_linear.minimum();
         info!("Minimum with linear difference {} -> {}", l_test, l_counter);
         info!("Finished calculation");
+        Answer::new(Some(s_counter), Some(l_counter))
+            .with("position with simple difference", s_test)
+            .with("position with linear difference", l_test)
     }
 
     // fn median(&self) -> usize {
@@ -114,6 +116,26 @@ impl Simulation {
     // }
 }
 
+impl Solution for Simulation {
+    fn parse(input: &str) -> Result<Self> {
+        Simulation::new(normalize(input))
+    }
+
+    fn part1(&self) -> Result<Option<usize>> {
+        let (_, counter) = Calculation::with_simple_diff(&self.numbers).minimum();
+        Ok(Some(counter))
+    }
+
+    fn part2(&self) -> Result<Option<usize>> {
+        let (_, counter) = Calculation::with_linear_diff(&self.numbers).minimum();
+        Ok(Some(counter))
+    }
+
+    fn answer(&self) -> Result<Answer> {
+        Ok(self.run())
+    }
+}
+
 impl fmt::Display for Simulation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self.numbers)
@@ -146,3 +168,43 @@ fn test_linear_difference() -> std::result::Result<(), String> {
 
     Ok(())
 }
+
+#[test]
+fn test_example_answer() -> Result<()> {
+    let answer = Simulation::new("16,1,2,0,4,2,7,1,2,14".to_string())?.run();
+
+    assert_eq!(answer.part1, Some(37));
+    assert_eq!(answer.part2, Some(168));
+    assert_eq!(answer.get("position with linear difference"), Some("5"));
+
+    Ok(())
+}
+
+/// `size` is the number of crabs, they start at positions up to twice that.
+impl Generate for Simulation {
+    const DEFAULT_SIZE: usize = 1000;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let positions: Vec<String> = (0..size)
+            .map(|_| rng.range(0..=2 * size).to_string())
+            .collect();
+        positions.join(",") + "\n"
+    }
+}
+
+#[test]
+fn test_generated_crabs_match_every_position() -> Result<()> {
+    // tries every position between the outermost crabs
+    aoc_core::check_generated(1..=100, |simulation: &Simulation| {
+        let numbers = &simulation.numbers;
+        let least_fuel = |fuel: fn(usize) -> usize| {
+            (numbers[0]..=numbers[numbers.len() - 1])
+                .map(|position| numbers.iter().map(|n| fuel(n.abs_diff(position))).sum())
+                .min()
+        };
+        [
+            least_fuel(|steps| steps),
+            least_fuel(|steps| (1..=steps).sum()),
+        ]
+    })
+}
@@ -1,185 +1,72 @@
 use std::{
-    cmp::{max, min},
     collections::{HashMap, HashSet},
     fmt,
-    result::Result,
 };
 
+use aoc_core::{normalize, Answer, Error, Generate, Part, Result, Rng, Solution};
+use aoc_grid::{palette, scale, Grid, Position, Render, Rgb};
 use log::{debug, info};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-struct Point {
-    x: usize,
-    y: usize,
-}
-
-impl Point {
-    fn new(x: usize, y: usize) -> Self {
-        Point { x, y }
-    }
-
-    fn is_neighbour_of(&self, other: &Point) -> bool {
-        let x = max(self.x, other.x) - min(self.x, other.x);
-        let x_same = self.x == other.x;
-        let y = max(self.y, other.y) - min(self.y, other.y);
-        let y_same = self.y == other.y;
-
-        (x_same && y == 1) || (x == 1 && y_same)
-    }
-}
-
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-struct Location {
-    value: usize,
-    is_lowest: bool,
-    point: Point,
-}
-
-impl Location {
-    fn new(value: usize, x: usize, y: usize) -> Self {
-        let point = Point::new(x, y);
-        Location {
-            value,
-            point,
-            is_lowest: false,
-        }
-    }
-
-    fn with_values(location: &Location, neighbour_values: Vec<usize>) -> Self {
-        let value = location.value;
-        let point = location.point;
-        let is_lowest = Self::check_lowest(value, neighbour_values);
-        Location {
-            value,
-            point,
-            is_lowest,
-        }
-    }
-
-    fn find_neighbours(&self, list: &[Location]) -> Vec<usize> {
-        let mut neighbours = Vec::new();
-        for location in list {
-            if location.is_neighbour_of(&self.point) {
-                neighbours.push(location);
-            }
-            if neighbours.len() == 4 {
-                break;
-            }
-        }
-        neighbours.iter().map(|location| location.value).collect()
-    }
-
-    fn find_basin_neighbours(&self, list: &[Location]) -> Vec<Location> {
-        let mut neighbours = Vec::new();
-        for location in list {
-            if self.is_neighbour_in_basin_of(location) {
-                neighbours.push(*location);
-            }
-            if neighbours.len() == 4 {
-                break;
-            }
-        }
-        neighbours
-    }
-
-    fn is_neighbour_of(&self, point: &Point) -> bool {
-        self.point.is_neighbour_of(point)
-    }
-
-    fn is_neighbour_in_basin_of(&self, other: &Location) -> bool {
-        other.value < 9 && other.is_neighbour_of(&self.point)
-    }
-
-    fn check_lowest(value: usize, neighbour_values: Vec<usize>) -> bool {
-        for other in &neighbour_values {
-            if value >= *other {
-                return false;
-            }
-        }
-        true
-    }
-
-    fn is_lowest(&self) -> bool {
-        self.is_lowest
-    }
-
-    fn risk_level(&self) -> usize {
-        self.value + 1
-    }
-}
-
-impl fmt::Display for Location {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.value == 9 {
-            write!(f, " ")
-        } else {
-            write!(f, "{}", self.value)
-        }
-    }
-}
+const BASIN_BORDER: u8 = 9;
 
 #[derive(Clone, Debug)]
 pub struct Simulation {
-    locations: Vec<Location>,
-    basins: HashMap<Point, HashSet<Location>>,
-    columns: usize,
+    heights: Grid<u8>,
+    low_points: Vec<Position>,
+    basins: HashMap<Position, HashSet<Position>>,
 }
 
 impl Simulation {
-    pub fn new(original_input: String) -> Self {
-        let mut locations: Vec<Location> = Vec::new();
-        let mut x = 0;
-        let mut columns = 0;
-        let basins = HashMap::new();
+    pub fn new(original_input: String) -> Result<Self> {
         debug!("\n{}", original_input);
-        for (y, line) in original_input.lines().enumerate() {
-            for val in line.chars() {
-                let value: u32 = val.to_digit(10).unwrap();
-                let location = Location::new(value as usize, x, y);
-                locations.push(location);
-                x += 1;
-            }
-            columns = x;
-            x = 0;
-        }
-        Simulation {
-            locations,
-            columns,
-            basins,
+        let heights = Grid::parse_with(&original_input, |val| {
+            val.to_digit(10)
+                .map(|value| value as u8)
+                .ok_or_else(|| format!("expected a height but got {:?}", val))
+        })?;
+        if heights.is_empty() {
+            return Err(Error::invalid("the height map is empty"));
         }
+        Ok(Simulation {
+            heights,
+            low_points: Vec::new(),
+            basins: HashMap::new(),
+        })
     }
 
     fn init_locations(&mut self) {
-        self.locations = self
-            .locations
+        self.low_points = self
+            .heights
             .iter()
-            .map(|l| {
-                let values = l.find_neighbours(&self.locations);
-                Location::with_values(l, values)
+            .filter(|(position, &value)| {
+                self.heights
+                    .neighbours4(*position)
+                    .all(|neighbour| value < self.heights[neighbour])
             })
-            // .map(|l| *l)
+            .map(|(position, _)| position)
             .collect()
     }
 
-    fn get_risk_levels(&self) {
+    fn risk_level(&self, position: Position) -> usize {
+        self.heights[position] as usize + 1
+    }
+
+    fn get_risk_levels(&self) -> usize {
         let sum: usize = self
-            .locations
+            .low_points
             .iter()
-            .filter(|l| l.is_lowest())
-            .map(|l| l.risk_level())
+            .map(|&position| self.risk_level(position))
             .sum();
         info!("Sum of risk levels is {}", sum);
+        sum
     }
 
     fn find_basins(&mut self) {
-        let mut basins: HashMap<Point, HashSet<Location>> = HashMap::new();
-        self.locations
-            .iter()
-            .filter(|l| l.is_lowest())
-            .for_each(|l| {
-                let basin = self.add_to_basin(HashSet::new(), vec![*l]);
-                basins.insert(l.point, basin);
-            });
+        let mut basins: HashMap<Position, HashSet<Position>> = HashMap::new();
+        self.low_points.iter().for_each(|&low_point| {
+            let basin = self.add_to_basin(HashSet::new(), vec![low_point]);
+            basins.insert(low_point, basin);
+        });
 
         self.basins = basins
     }
@@ -189,62 +76,173 @@ impl Simulation {
      */
     fn add_to_basin(
         &self,
-        mut basin: HashSet<Location>,
-        to_check: Vec<Location>,
-    ) -> HashSet<Location> {
+        mut basin: HashSet<Position>,
+        to_check: Vec<Position>,
+    ) -> HashSet<Position> {
         // can be at most that many
         let max = to_check.len() * 4;
-        let mut additional: Vec<Location> = Vec::with_capacity(max);
+        let mut additional: Vec<Position> = Vec::with_capacity(max);
         // end-recursive return
         if to_check.is_empty() {
             return basin;
         }
         basin.reserve(to_check.len());
-        for location in to_check {
+        for position in to_check {
             // succesively push the locations into the known space
-            if !basin.insert(location) {
+            if !basin.insert(position) {
                 // location was already known, do not double check
                 continue;
             }
             // for that location, find the potential neighbours
-            let mut l_neighbours = location.find_basin_neighbours(&self.locations);
-            additional.append(&mut l_neighbours);
+            additional.extend(
+                self.heights
+                    .neighbours4(position)
+                    .filter(|&neighbour| self.heights[neighbour] < BASIN_BORDER),
+            );
         }
 
         self.add_to_basin(basin, additional)
     }
 
-    fn get_basin_sizes(&self) {
-        let mut basin_sizes: Vec<usize> = self.basins.iter().map(|(_, set)| set.len()).collect();
+    fn get_basin_sizes(&self) -> usize {
+        let mut basin_sizes: Vec<usize> = self.basins.values().map(|set| set.len()).collect();
         basin_sizes.sort_unstable();
         let product: usize = basin_sizes.iter().rev().take(3).copied().product();
         info!("Product of 3 largest basin sizes levels is {}", product);
+        product
     }
 
-    pub fn run(&mut self) -> Result<(), ()> {
+    pub fn run(&mut self) -> Result<Answer> {
         info!("Running Simulation");
         self.init_locations();
         debug!("{}", self);
-        self.get_risk_levels();
+        let risk_levels = self.get_risk_levels();
         self.find_basins();
-        self.get_basin_sizes();
+        let basin_sizes = self.get_basin_sizes();
         info!("Done running Simulation");
-        Ok(())
+        Ok(Answer::new(Some(risk_levels), Some(basin_sizes)).with("low points", self.basins.len()))
     }
 }
 
 impl fmt::Display for Simulation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut o = String::from("\n");
-        let mut column = 0;
-        for location in &self.locations {
-            o.push_str(&location.to_string());
-            column += 1;
-            if column == self.columns {
-                o.push('\n');
-                column = 0;
+        let map = self.heights.map(|&value| match value {
+            BASIN_BORDER => ' ',
+            _ => char::from(b'0' + value),
+        });
+        writeln!(f)?;
+        writeln!(f, "{}", map)
+    }
+}
+
+impl Solution for Simulation {
+    fn parse(input: &str) -> Result<Self> {
+        Simulation::new(normalize(input))
+    }
+
+    fn part1(&self) -> Result<Option<usize>> {
+        let mut simulation = self.clone();
+        simulation.init_locations();
+        Ok(Some(simulation.get_risk_levels()))
+    }
+
+    fn part2(&self) -> Result<Option<usize>> {
+        let mut simulation = self.clone();
+        simulation.init_locations();
+        simulation.find_basins();
+        Ok(Some(simulation.get_basin_sizes()))
+    }
+
+    fn answer(&self) -> Result<Answer> {
+        self.clone().run()
+    }
+}
+
+impl Render for Simulation {
+    fn render(&self, part: Part) -> Grid<Rgb> {
+        let mut simulation = self.clone();
+        simulation.init_locations();
+
+        match part {
+            Part::One => {
+                let mut image = self
+                    .heights
+                    .map(|&value| scale(value as usize, BASIN_BORDER as usize));
+                for &low_point in &simulation.low_points {
+                    image[low_point] = Rgb::WHITE;
+                }
+                image
             }
+            Part::Two => {
+                simulation.find_basins();
+                let mut image = self.heights.map(|_| Rgb::BLACK);
+                // low points are found row by row, which keeps the colours stable
+                for (index, low_point) in simulation.low_points.iter().enumerate() {
+                    for &position in &simulation.basins[low_point] {
+                        let depth = self.heights[position] as f64 / BASIN_BORDER as f64;
+                        image[position] = palette(index).mix(Rgb::BLACK, depth * 0.6);
+                    }
+                }
+                image
+            }
+        }
+    }
+}
+
+/// `size` is the width and height of the height map.
+impl Generate for Simulation {
+    const DEFAULT_SIZE: usize = 100;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut output = String::with_capacity((size + 1) * size);
+        for _ in 0..size {
+            output.extend((0..size).map(|_| char::from(b'0' + rng.range(0..=9) as u8)));
+            output.push('\n');
         }
-        writeln!(f, "{}", o)
+        output
     }
 }
+
+#[test]
+fn test_generated_maps_match_flooded_basins() -> Result<()> {
+    aoc_core::check_generated(1..=30, |simulation: &Simulation| {
+        let heights = &simulation.heights;
+        let (rows, columns) = (heights.rows(), heights.columns());
+        let neighbours = |(row, column): Position| {
+            [(0, 1), (2, 1), (1, 0), (1, 2)]
+                .into_iter()
+                .map(move |(dr, dc)| ((row + dr).wrapping_sub(1), (column + dc).wrapping_sub(1)))
+                .filter(move |&(row, column)| row < rows && column < columns)
+        };
+        let low_points: Vec<Position> = heights
+            .positions()
+            .filter(|&position| {
+                neighbours(position).all(|neighbour| heights[position] < heights[neighbour])
+            })
+            .collect();
+        let risk: usize = low_points
+            .iter()
+            .map(|&position| heights[position] as usize + 1)
+            .sum();
+
+        // floods from every low point over everything lower than the border
+        let mut sizes: Vec<usize> = low_points
+            .iter()
+            .map(|&low_point| {
+                let mut basin = HashSet::from([low_point]);
+                let mut to_check = vec![low_point];
+                while let Some(position) = to_check.pop() {
+                    for neighbour in neighbours(position) {
+                        if heights[neighbour] < BASIN_BORDER && basin.insert(neighbour) {
+                            to_check.push(neighbour);
+                        }
+                    }
+                }
+                basin.len()
+            })
+            .collect();
+        sizes.sort_unstable();
+
+        [Some(risk), Some(sizes.iter().rev().take(3).product())]
+    })
+}
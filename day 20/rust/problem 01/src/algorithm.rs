@@ -1,15 +1,62 @@
+use std::fmt;
+
+use aoc_core::Error;
 use arrayvec::ArrayString;
 
+use crate::image::Pixel;
+
 extern crate arrayvec;
 
+pub(crate) const ALGORITHM_SIZE: usize = 512;
+
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct IEAlgorithm {
-    description: ArrayString<512>,
+    description: ArrayString<ALGORITHM_SIZE>,
 }
 
 impl IEAlgorithm {
-    pub(crate) fn new(input: String) -> Self {
-        let description = ArrayString::from(&input).unwrap();
-        Self { description }
+    /// Reads the algorithm, which may be wrapped over several lines.
+    pub(crate) fn new(input: Vec<&str>) -> Result<Self, Error> {
+        let mut description = ArrayString::new();
+        for (n, line) in input.iter().enumerate() {
+            for (column, character) in line.chars().enumerate() {
+                if character != '#' && character != '.' {
+                    return Err(Error::parse(
+                        n + 1,
+                        column + 1,
+                        format!("expected '#' or '.' but got {:?}", character),
+                    ));
+                }
+                description.try_push(character).map_err(|_| {
+                    Error::parse(
+                        n + 1,
+                        column + 1,
+                        format!("the algorithm is longer than {} pixels", ALGORITHM_SIZE),
+                    )
+                })?;
+            }
+        }
+        if !description.is_full() {
+            return Err(Error::invalid(format!(
+                "the algorithm needs {} pixels but has {}",
+                ALGORITHM_SIZE,
+                description.len()
+            )));
+        }
+        Ok(Self { description })
+    }
+
+    /// The output pixel for the square of input pixels read as the binary number `index`.
+    pub(crate) fn enhanced(&self, index: usize) -> Pixel {
+        match self.description.as_bytes()[index] {
+            b'#' => Pixel::Light,
+            _ => Pixel::Dark,
+        }
+    }
+}
+
+impl fmt::Display for IEAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description)
     }
 }
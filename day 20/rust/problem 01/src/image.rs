@@ -1,62 +1,117 @@
 use std::fmt;
 
-use quadtree_rs::Quadtree;
+use aoc_core::Error;
+use aoc_grid::Grid;
 
-#[derive(Clone, Copy, Debug)]
-struct Dark;
-#[derive(Clone, Copy, Debug)]
-struct Light;
+use crate::algorithm::{IEAlgorithm, ALGORITHM_SIZE};
 
-#[derive(Clone, Copy, Debug)]
-enum Pixel {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Pixel {
     Dark,
     Light,
 }
 
-#[derive(Debug)]
+impl Pixel {
+    fn from_char(character: char) -> Result<Self, String> {
+        match character {
+            '#' => Ok(Pixel::Light),
+            '.' => Ok(Pixel::Dark),
+            other => Err(format!("expected '#' or '.' but got {:?}", other)),
+        }
+    }
+}
+
+impl fmt::Display for Pixel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pixel::Dark => write!(f, "."),
+            Pixel::Light => write!(f, "#"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub(crate) struct Image {
-    internal: Quadtree<usize, Pixel>,
-    current_depth: usize,
-    input_image: String,
+    pixels: Grid<Pixel>,
+    /// The image is infinite, every pixel outside of `pixels` looks like this.
+    background: Pixel,
 }
 
 impl Image {
-    fn new(depth: usize) -> Self {
-        let current_depth = depth;
-        let internal = Quadtree::new(current_depth);
-        let input_image = String::new();
-
-        Self {
-            internal,
-            current_depth,
-            input_image,
+    /// Reads the image from lines numbered as in the puzzle input.
+    pub(crate) fn with_starting_image(input: Vec<(usize, &str)>) -> Result<Self, Error> {
+        let first = input.iter().position(|(_, l)| !l.trim().is_empty());
+        let last = input.iter().rposition(|(_, l)| !l.trim().is_empty());
+        let (lines, start) = match (first, last) {
+            (Some(first), Some(last)) => (&input[first..=last], input[first].0),
+            _ => (&input[..0], 1),
+        };
+        let text: Vec<&str> = lines.iter().map(|(_, l)| *l).collect();
+        let pixels = Grid::parse_with(&text.join("\n"), Pixel::from_char)
+            .map_err(|e| e.relative_to(start, 1))?;
+
+        Ok(Self {
+            pixels,
+            background: Pixel::Dark,
+        })
+    }
+
+    fn pixel(&self, row: isize, column: isize) -> Pixel {
+        match (usize::try_from(row), usize::try_from(column)) {
+            (Ok(row), Ok(column)) => self
+                .pixels
+                .get((row, column))
+                .copied()
+                .unwrap_or(self.background),
+            _ => self.background,
         }
     }
 
-    pub(crate) fn with_starting_image(input: String) -> Self {
-        let len: usize = input.lines().count();
-        let mut image = Self::new(len);
-        image.init_image(input.lines().collect());
+    /// Applies `algorithm` once, the image grows by one pixel on every side.
+    pub(crate) fn enhance(&self, algorithm: &IEAlgorithm) -> Self {
+        let pixels = Grid::from_fn(
+            self.pixels.rows() + 2,
+            self.pixels.columns() + 2,
+            |(row, column)| {
+                // the square around (row - 1, column - 1) of this image, read as a binary number
+                let (row, column) = (row as isize - 1, column as isize - 1);
+                let index = (-1..=1)
+                    .flat_map(|dr| (-1..=1).map(move |dc| (dr, dc)))
+                    .fold(0, |index, (dr, dc)| {
+                        index << 1 | (self.pixel(row + dr, column + dc) == Pixel::Light) as usize
+                    });
+                algorithm.enhanced(index)
+            },
+        );
+        let background = algorithm.enhanced(match self.background {
+            Pixel::Dark => 0,
+            Pixel::Light => ALGORITHM_SIZE - 1,
+        });
+
+        Self { pixels, background }
+    }
 
-        image
+    pub(crate) fn pixels(&self) -> &Grid<Pixel> {
+        &self.pixels
     }
 
-    fn init_image(&mut self, input: Vec<&str>) {
-        for (x, line) in input.iter().enumerate() {
-            for (y, character) in line.chars().enumerate() {
-                if character == '#' {
-                    self.internal.insert_pt((x, y).into(), Pixel::Light);
-                }
-            }
+    /// The number of lit pixels, `None` if infinitely many are lit.
+    pub(crate) fn count_lit(&self) -> Option<usize> {
+        match self.background {
+            Pixel::Dark => Some(
+                self.pixels
+                    .values()
+                    .filter(|&&pixel| pixel == Pixel::Light)
+                    .count(),
+            ),
+            Pixel::Light => None,
         }
     }
 }
 
 impl fmt::Display for Image {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut o = String::from("\n");
-        let width = self.internal.width();
-        for location in self.internal.values() {}
-        writeln!(f, "{}", o)
+        writeln!(f)?;
+        writeln!(f, "{}", self.pixels)
     }
 }
@@ -2,8 +2,14 @@ mod algorithm;
 mod image;
 
 use algorithm::IEAlgorithm;
-use image::Image;
-use std::{fmt, result::Result};
+use aoc_core::{normalize, Answer, Error, Generate, Part, Result, Rng, Solution};
+use aoc_grid::{Grid, Render, Rgb};
+use image::{Image, Pixel};
+use log::{debug, info};
+use std::fmt;
+
+const SHORT_ENHANCE_TIMES: usize = 2;
+const ENHANCE_TIMES: usize = 50;
 
 #[derive(Debug)]
 pub struct Simulation {
@@ -12,22 +18,153 @@ pub struct Simulation {
 }
 
 impl Simulation {
-    pub fn new(input: String) -> Self {
-        let algo_input = input.lines().take(1).collect();
-        let algorithm = IEAlgorithm::new(algo_input);
-        let image_input = input.lines().skip(1).collect();
-        let image = Image::with_starting_image(image_input);
+    pub fn new(input: String) -> Result<Self> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(n, line)| (n + 1, line))
+            .skip_while(|(_, line)| line.trim().is_empty())
+            .peekable();
+        let algo_start = lines.peek().map_or(1, |(n, _)| *n);
+        // the algorithm ends at the first blank line
+        let algo_input = lines
+            .by_ref()
+            .map_while(|(_, line)| (!line.trim().is_empty()).then_some(line))
+            .collect();
+        let algorithm = IEAlgorithm::new(algo_input).map_err(|e| e.relative_to(algo_start, 1))?;
+        let image = Image::with_starting_image(lines.collect())?;
+
+        Ok(Self { algorithm, image })
+    }
+
+    fn enhance(&self, times: usize) -> Image {
+        let mut image = self.image.clone();
+        for step in 1..=times {
+            image = image.enhance(&self.algorithm);
+            debug!("after {} enhancements:{}", step, image);
+        }
+        image
+    }
+
+    /// Enhances the image `times` times and counts the lit pixels.
+    fn lit_after(&self, times: usize) -> Result<usize> {
+        let lit = self.enhance(times).count_lit().ok_or_else(|| {
+            Error::invalid(format!(
+                "infinitely many pixels are lit after {} enhancements",
+                times
+            ))
+        })?;
+        info!("{} pixels are lit after {} enhancements", lit, times);
+        Ok(lit)
+    }
+
+    pub fn run(&self) -> Result<Answer> {
+        let lit = self.image.count_lit().unwrap_or_default();
+
+        Ok(Answer::new(
+            Some(self.lit_after(SHORT_ENHANCE_TIMES)?),
+            Some(self.lit_after(ENHANCE_TIMES)?),
+        )
+        .with("lit pixels", lit))
+    }
+}
+
+impl Solution for Simulation {
+    fn parse(input: &str) -> Result<Self> {
+        Simulation::new(normalize(input))
+    }
 
-        Self { algorithm, image }
+    fn part1(&self) -> Result<Option<usize>> {
+        Ok(Some(self.lit_after(SHORT_ENHANCE_TIMES)?))
     }
 
-    pub fn run(&mut self) -> Result<(), ()> {
-        Ok(())
+    fn part2(&self) -> Result<Option<usize>> {
+        Ok(Some(self.lit_after(ENHANCE_TIMES)?))
+    }
+
+    fn answer(&self) -> Result<Answer> {
+        self.run()
     }
 }
 
 impl fmt::Display for Simulation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.algorithm)?;
         writeln!(f, "{}", self.image)
     }
 }
+
+impl Render for Simulation {
+    fn render(&self, part: Part) -> Grid<Rgb> {
+        let times = match part {
+            Part::One => SHORT_ENHANCE_TIMES,
+            Part::Two => ENHANCE_TIMES,
+        };
+
+        self.enhance(times).pixels().map(|pixel| match pixel {
+            Pixel::Light => Rgb::WHITE,
+            Pixel::Dark => Rgb::BLACK,
+        })
+    }
+}
+
+/// `size` is the width and height of the starting image.
+impl Generate for Simulation {
+    const DEFAULT_SIZE: usize = 100;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut pixel = |_| if rng.chance(0.5) { '#' } else { '.' };
+        let mut algorithm: Vec<char> = (0..algorithm::ALGORITHM_SIZE).map(&mut pixel).collect();
+        // a dark background that lights up has to go dark again, or infinitely many pixels stay lit
+        if algorithm[0] == '#' {
+            algorithm[algorithm::ALGORITHM_SIZE - 1] = '.';
+        }
+
+        let mut output: String = algorithm.into_iter().collect();
+        output.push_str("\n\n");
+        for _ in 0..size {
+            output.extend((0..size).map(&mut pixel));
+            output.push('\n');
+        }
+        output
+    }
+}
+
+#[test]
+fn test_generated_images_match_a_padded_image() -> Result<()> {
+    // pads the image with one more dark pixel than it grows per side, so that the outermost
+    // pixels always show the background, and reads beyond them as more of those
+    aoc_core::check_generated(0..=4, |simulation: &Simulation| {
+        let image = simulation.image.pixels();
+        let enhance = |times: usize| {
+            let padding = times + 1;
+            let (rows, columns) = (image.rows() + 2 * padding, image.columns() + 2 * padding);
+            let mut pixels = vec![vec![false; columns]; rows];
+            for ((row, column), &pixel) in image.iter() {
+                pixels[row + padding][column + padding] = pixel == Pixel::Light;
+            }
+            let around = |i: usize, len: usize| [i.saturating_sub(1), i, (i + 1).min(len - 1)];
+            for _ in 0..times {
+                pixels = (0..rows)
+                    .map(|row| {
+                        (0..columns)
+                            .map(|column| {
+                                let index = around(row, rows).iter().fold(0, |index, &r| {
+                                    around(column, columns)
+                                        .iter()
+                                        .fold(index, |index, &c| index << 1 | pixels[r][c] as usize)
+                                });
+                                simulation.algorithm.enhanced(index) == Pixel::Light
+                            })
+                            .collect()
+                    })
+                    .collect();
+            }
+            pixels.iter().flatten().filter(|&&lit| lit).count()
+        };
+        [
+            Some(enhance(SHORT_ENHANCE_TIMES)),
+            Some(enhance(ENHANCE_TIMES)),
+        ]
+    })
+}
@@ -10,41 +10,36 @@ const WINNING_SCORE: usize = 1000;
 const DICE_THROW_TIMES: usize = 3;
 
 #[derive(Clone, Debug)]
-struct DiracDiceBoard {
-    players: &'static Vec<Player>,
+pub(crate) struct DiracDiceBoard {
+    players: Vec<Player>,
     next_player_index: usize,
-    next_player: &'static Player,
-    positions: usize,
     die: Det100Die,
     player_won_index: Option<usize>,
 }
 
 impl DiracDiceBoard {
-    fn new(players: &'static Vec<Player>) -> Self {
-        let positions = BOARD_SIZE;
+    pub(crate) fn new(players: Vec<Player>) -> Self {
         let die = Det100Die::new();
         let next_player_index = 0;
-        let next_player = players.get(next_player_index).unwrap();
         let player_won_index = None;
 
         Self {
             players,
             next_player_index,
-            next_player,
-            positions,
             die,
             player_won_index,
         }
     }
 
     fn next_round(&mut self) {
-        let mut player = &self.next_player();
+        let mut player = *self.next_player();
 
         (0..DICE_THROW_TIMES).for_each(|_| {
-            let result = &self.die.roll();
-            self.advance_player(*player, *result);
+            let result = self.die.roll();
+            Self::advance_player(&mut player, result);
         });
         player.recalculate_score();
+        *self.next_player() = player;
 
         info!(
             "Player {} moves to space {} for a total score of {}.",
@@ -53,20 +48,21 @@ impl DiracDiceBoard {
             player.score()
         );
 
-        if Self::is_winner(**player) {
+        if Self::is_winner(player) {
             self.player_won_index = Some(self.next_player_index);
             return;
         }
 
-        self.next_player_index = (self.next_player_index + 1) % (self.players.len() - 1)
+        self.next_player_index = (self.next_player_index + 1) % self.players.len()
     }
 
     fn next_player(&mut self) -> &mut Player {
         self.players.get_mut(self.next_player_index).unwrap()
     }
 
-    fn advance_player(&mut self, player: &mut Player, throw: usize) {
-        player.advance_to((player.current_position() + throw) % BOARD_SIZE)
+    fn advance_player(player: &mut Player, throw: usize) {
+        // spaces are numbered 1 to 10
+        player.advance_to((player.current_position() + throw - 1) % BOARD_SIZE + 1)
     }
 
     fn is_game_over(&self) -> bool {
@@ -74,6 +70,27 @@ impl DiracDiceBoard {
     }
 
     fn is_winner(player: Player) -> bool {
-        player.score() == WINNING_SCORE
+        player.score() >= WINNING_SCORE
+    }
+
+    pub(crate) fn die_rolls(&self) -> usize {
+        self.die.counter()
+    }
+
+    /// Plays until one player wins, returns the losing score times the number of die rolls.
+    pub(crate) fn play(&mut self) -> usize {
+        while !self.is_game_over() {
+            self.next_round();
+        }
+
+        let losing_score = self
+            .players
+            .iter()
+            .filter(|player| !Self::is_winner(**player))
+            .map(|player| player.score())
+            .min()
+            .unwrap_or(0);
+
+        losing_score * self.die.counter()
     }
 }
@@ -33,6 +33,7 @@ impl Default for Det100Die {
 impl Die for Det100Die {
     fn roll(&mut self) -> usize {
         self.counter += 1;
-        (self.start + self.counter) % self.sides
+        // the die shows 1 to 100
+        (self.start + self.counter - 1) % self.sides + 1
     }
 }
@@ -4,34 +4,136 @@ mod player;
 
 use std::fmt;
 
-use die::Die;
+use aoc_core::{normalize, parse_token, Answer, Error, Generate, Result, Rng, Solution};
 use log::{debug, info};
 
-use crate::die::Det100Die;
+use crate::{board::DiracDiceBoard, player::Player};
 
+#[derive(Clone, Debug)]
 pub struct Simulation {
-    input: String,
-    die: Det100Die,
+    players: Vec<Player>,
 }
 
 impl Simulation {
-    pub fn new(input: String) -> Self {
+    pub fn new(input: String) -> Result<Self> {
         debug!("Got input {}", input);
-        let die = Det100Die::new();
-        Self { input, die }
+        let mut players = Vec::new();
+        for (n, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (_, position) = line.split_once("starting position:").ok_or_else(|| {
+                Error::parse(
+                    n + 1,
+                    1,
+                    "expected a line like \"Player 1 starting position: 4\"",
+                )
+            })?;
+            let position: usize =
+                parse_token(line, position.trim()).map_err(|e| e.relative_to(n + 1, 1))?;
+            if !(1..=10).contains(&position) {
+                return Err(Error::invalid(format!(
+                    "line {}: there is no space {} on the board",
+                    n + 1,
+                    position
+                )));
+            }
+            players.push(Player::new(players.len() + 1, position));
+        }
+        if players.is_empty() {
+            return Err(Error::invalid("there are no players"));
+        }
+        Ok(Self { players })
     }
 
-    pub fn run(&mut self) -> Result<(), ()> {
+    /// Plays with the deterministic die, returns the result and how often the die was rolled.
+    fn play_deterministic(&self) -> (usize, usize) {
+        let mut board = DiracDiceBoard::new(self.players.clone());
+        let result = board.play();
+        (result, board.die_rolls())
+    }
+
+    pub fn run(&mut self) -> Result<Answer> {
         info!("Running Simulation");
-        self.input += ".";
-        self.die.roll();
+        let (result, rolls) = self.play_deterministic();
+        info!("Deterministic game ends with {}", result);
         info!("Ending Simulation");
-        Ok(())
+        Ok(Answer::new(Some(result), None).with("die rolls", rolls))
+    }
+}
+
+impl Solution for Simulation {
+    fn parse(input: &str) -> Result<Self> {
+        Simulation::new(normalize(input))
+    }
+
+    fn part1(&self) -> Result<Option<usize>> {
+        let (result, _) = self.play_deterministic();
+        Ok(Some(result))
+    }
+
+    fn part2(&self) -> Result<Option<usize>> {
+        Ok(None)
+    }
+
+    fn answer(&self) -> Result<Answer> {
+        self.clone().run()
     }
 }
 
 impl fmt::Display for Simulation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "woop")
+        for player in &self.players {
+            writeln!(
+                f,
+                "Player {} starting position: {}",
+                player.number(),
+                player.current_position()
+            )?;
+        }
+        Ok(())
     }
 }
+
+/// `size` is the number of players.
+impl Generate for Simulation {
+    const DEFAULT_SIZE: usize = 2;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (1..=size)
+            .map(|player| {
+                format!(
+                    "Player {} starting position: {}\n",
+                    player,
+                    rng.range(1..=10)
+                )
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_generated_games_match_a_plain_game() -> Result<()> {
+    // every player in turn rolls three times and moves, until one of them has 1000 points
+    aoc_core::check_generated(1..=4, |simulation: &Simulation| {
+        let mut positions: Vec<usize> = simulation
+            .players
+            .iter()
+            .map(|player| player.current_position())
+            .collect();
+        let mut scores = vec![0; positions.len()];
+        let mut rolls = 0;
+        for player in (0..positions.len()).cycle() {
+            for _ in 0..3 {
+                rolls += 1;
+                positions[player] = (positions[player] + (rolls - 1) % 100) % 10 + 1;
+            }
+            scores[player] += positions[player];
+            if scores[player] >= 1000 {
+                break;
+            }
+        }
+        let losing = scores.iter().filter(|&&score| score < 1000).min();
+        [Some(losing.copied().unwrap_or(0) * rolls), None]
+    })
+}